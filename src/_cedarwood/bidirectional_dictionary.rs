@@ -0,0 +1,244 @@
+use crate::{
+    Dictionary,
+    Normalization,
+    PatternEntry,
+    RemovedPattern,
+    UltraNLPResult,
+};
+use crate::cedarwood::{
+    BackwardDictionary,
+    ForwardDictionary,
+};
+
+/// A `ForwardDictionary` and a `BackwardDictionary` built from the same patterns,
+/// which applies every insertion and removal to both of them,
+/// so that an index of patterns refers to the same pattern in both directions.
+///
+/// The values are kept here, borrow them by `Match::value` with this dictionary.
+#[derive(Clone)]
+pub struct BidirectionalDictionary<V = ()> {
+    forward_dict: ForwardDictionary,
+    backward_dict: BackwardDictionary,
+    // 第i个值属于第i个模式.
+    values: Vec<V>,
+}
+
+impl<V> BidirectionalDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let forward_dict = ForwardDictionary::new_with_normalization(
+            patterns_with_entries
+                .iter()
+                .map(|(pattern, entry)| (pattern.as_ref(), entry.clone())),
+            normalization,
+        )?;
+        let backward_dict = BackwardDictionary::new_with_normalization(
+            patterns_with_entries,
+            normalization,
+        )?;
+
+        Ok(Self { forward_dict, backward_dict, values })
+    }
+
+    pub fn forward_dictionary(&self) -> &ForwardDictionary {
+        &self.forward_dict
+    }
+
+    pub fn backward_dictionary(&self) -> &BackwardDictionary {
+        &self.backward_dict
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.forward_dict.entry(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.forward_dict.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.forward_dict.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.forward_dict.normalization()
+    }
+
+    /// Inserts a pattern with its value into both dictionaries,
+    /// or replaces the entry and the value of an existing pattern and returns the old ones.
+    pub fn insert_with_value<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+        value: V,
+    ) -> UltraNLPResult<Option<(PatternEntry, V)>> {
+        let pattern = pattern.as_ref();
+        let old_entry = self.forward_dict.insert(pattern, entry.clone())?;
+        // 两个词典的模式相同, 因此插入的结果也相同.
+        self.backward_dict.insert(pattern, entry)?;
+
+        match old_entry {
+            Some(old_entry) => {
+                let index = self.forward_dict
+                    .index_of_pattern(pattern)
+                    // 模式刚被替换, 一定存在.
+                    .unwrap();
+                let old_value = std::mem::replace(&mut self.values[index], value);
+
+                Ok(Some((old_entry, old_value)))
+            },
+            None => {
+                self.values.push(value);
+
+                Ok(None)
+            },
+        }
+    }
+
+    /// Removes a pattern from both dictionaries and returns its entry and its value.
+    ///
+    /// The last pattern takes the index of the removed pattern in both dictionaries,
+    /// see `RemovedPattern::moved_index_of_patterns`.
+    pub fn remove<T: AsRef<str>>(&mut self, pattern: T) -> Option<RemovedPattern<V>> {
+        let pattern = pattern.as_ref();
+        let removed = self.forward_dict.remove(pattern)?;
+        self.backward_dict.remove(pattern);
+
+        let index_of_patterns = removed.index_of_patterns();
+        let moved_index_of_patterns = removed.moved_index_of_patterns();
+        let (entry, _) = removed.into_entry_and_value();
+        let value = self.values.swap_remove(index_of_patterns);
+
+        Some(RemovedPattern::new(entry, value, index_of_patterns, moved_index_of_patterns))
+    }
+}
+
+impl BidirectionalDictionary {
+    /// Inserts a pattern into both dictionaries,
+    /// or replaces the entry of an existing pattern and returns the old entry.
+    pub fn insert<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+    ) -> UltraNLPResult<Option<PatternEntry>> {
+        self
+            .insert_with_value(pattern, entry, ())
+            .map(|replaced| replaced.map(|(entry, _)| entry))
+    }
+}
+
+impl<V> Dictionary for BidirectionalDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, Dictionary, Normalization, PatternEntry};
+    use crate::cedarwood::{
+        segment_backward_longest,
+        segment_bidirectional_longest,
+        BidirectionalDictionary,
+    };
+
+    #[test]
+    fn test_insert_and_remove() {
+        let text = "当下雨天";
+        let mut dict = BidirectionalDictionary::new(vec!["当", "当下", "雨天", "积水"]).unwrap();
+
+        let removed = dict.remove("当").unwrap();
+        dict.insert("下雨天", PatternEntry::default()).unwrap();
+
+        assert_eq!(removed.index_of_patterns(), 0);
+        assert_eq!(removed.moved_index_of_patterns(), Some(3));
+        assert_eq!(
+            segment_bidirectional_longest(
+                text,
+                dict.forward_dictionary(),
+                dict.backward_dictionary(),
+                BehaviorForUnmatched::KeepAsChars,
+            )
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("当下", Some(1)), ("雨天", Some(2))]
+        );
+        assert_eq!(
+            segment_backward_longest(text, dict.backward_dictionary(), BehaviorForUnmatched::KeepAsChars)
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("当", None), ("下雨天", Some(3))]
+        );
+    }
+
+    #[test]
+    fn test_insert_with_value_and_remove() {
+        let text = "商品和服务";
+        let mut dict = BidirectionalDictionary::new_with_values(
+            vec![
+                ("商品", PatternEntry::default(), 1),
+                ("和服", PatternEntry::default(), 2),
+            ],
+            Normalization::default(),
+        ).unwrap();
+
+        let inserted = dict.insert_with_value("服务", PatternEntry::default(), 3).unwrap();
+        let replaced = dict.insert_with_value("商品", PatternEntry::default(), 10).unwrap();
+        let removed = dict.remove("和服").map(|x| x.into_entry_and_value());
+
+        assert!(inserted.is_none());
+        assert_eq!(replaced, Some((PatternEntry::default(), 1)));
+        assert_eq!(removed, Some((PatternEntry::default(), 2)));
+        assert_eq!(
+            segment_backward_longest(text, dict.backward_dictionary(), BehaviorForUnmatched::Ignore)
+                .into_iter()
+                .map(|x| x.value(&dict).copied())
+                .collect::<Vec<_>>(),
+            vec![Some(10), Some(3)]
+        );
+    }
+}
@@ -1,39 +1,357 @@
-use std::collections::HashSet;
+use std::path::Path;
 use cedarwood::Cedar;
 use crate::{
+    Dictionary,
+    Normalization,
+    PatternEntry,
+    RemovedPattern,
     UltraNLPResult,
     UltraNLPError,
+    dag::Dag,
+    dictionary::validate_patterns,
+    SerializableValue,
+    serialization::{
+        decode,
+        encode,
+        read_file,
+        validate_max_pattern_chars,
+        write_file,
+        Decoder,
+        DictionaryKind,
+        Encoder,
+    },
+    pattern_entry::{
+        PatternEntries,
+        with_default_entries,
+        with_frequency_entries,
+        with_tagged_frequency_entries,
+    },
 };
 
 #[derive(Clone)]
-pub struct ForwardDictionary {
+pub struct ForwardDictionary<V = ()> {
     pub(crate) dat: Cedar,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // Cedar无法列出其中的模式, 因此为序列化保留规范化后的模式.
+    pub(crate) patterns: Vec<String>,
+    // 第i个值属于第i个模式.
+    pub(crate) values: Vec<V>,
 }
 
 #[derive(Clone)]
-pub struct BackwardDictionary {
+pub struct BackwardDictionary<V = ()> {
     pub(crate) dat: Cedar,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // Cedar无法列出其中的模式, 因此为序列化保留规范化后的模式.
+    pub(crate) patterns: Vec<String>,
+    // 第i个值属于第i个模式.
+    pub(crate) values: Vec<V>,
+}
+
+impl<V> ForwardDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
+        let patterns = patterns_with_values
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect::<Vec<_>>();
+        let dat = create_forward_dat(&patterns)?;
+
+        Ok(Self { dat, entries, patterns, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Inserts a pattern with its value,
+    /// or replaces the entry and the value of an existing pattern and returns the old ones.
+    pub fn insert_with_value<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+        value: V,
+    ) -> UltraNLPResult<Option<(PatternEntry, V)>> {
+        insert_pattern(
+            &mut self.dat,
+            &mut self.entries,
+            &mut self.patterns,
+            &mut self.values,
+            // 与分词时对文本的规范化保持一致.
+            self.normalization.normalize(pattern.as_ref()),
+            (entry, value),
+            forward_key,
+        )
+    }
+
+    /// Removes a pattern and returns its entry and its value.
+    ///
+    /// The last pattern takes the index of the removed pattern,
+    /// see `RemovedPattern::moved_index_of_patterns`.
+    /// Use `BidirectionalDictionary` to keep the indexes of the forward and backward dictionaries consistent.
+    pub fn remove<T: AsRef<str>>(&mut self, pattern: T) -> Option<RemovedPattern<V>> {
+        remove_pattern(
+            &mut self.dat,
+            &mut self.entries,
+            &mut self.patterns,
+            &mut self.values,
+            // 与分词时对文本的规范化保持一致.
+            self.normalization.normalize(pattern.as_ref()),
+            forward_key,
+        )
+    }
+
+    pub(crate) fn index_of_pattern(&self, pattern: &str) -> Option<usize> {
+        let key = forward_key(&self.normalization.normalize(pattern));
+
+        self.dat
+            .exact_match_search(&key)
+            .map(|(id, _, _)| {
+                usize::try_from(id)
+                    // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
+                    .unwrap()
+            })
+    }
+
+    /// Builds the DAG of all words in the normalized text,
+    /// the chars where no word starts are filled as single char edges.
+    pub(crate) fn build_dag(&self, text: &str) -> Dag {
+        let mut dag = Dag::new(text);
+        text
+            .char_indices()
+            .for_each(|(start_index, _)| {
+                // 注意, 虽然不知道这个Option的意义, 但Option是Some不代表matches非空.
+                if let Some(matches) = self.dat.common_prefix_search(&text[start_index..]) {
+                    matches
+                        .into_iter()
+                        .for_each(|(id, length)| {
+                            let value = usize::try_from(id)
+                                // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
+                                .unwrap();
+
+                            dag.add_edge(start_index, start_index + length + 1, value);
+                        });
+                }
+            });
+        dag.fill_unmatched_chars(text);
+
+        dag
+    }
 }
 
 impl ForwardDictionary {
     pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
         patterns: I
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
-        if patterns_with_values.len() == 0 {
-            return Err(UltraNLPError::new("The patterns cannot be empty"));
-        }
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
+
+    /// Inserts a pattern, or replaces the entry of an existing pattern and returns the old entry.
+    pub fn insert<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+    ) -> UltraNLPResult<Option<PatternEntry>> {
+        self
+            .insert_with_value(pattern, entry, ())
+            .map(|replaced| replaced.map(|(entry, _)| entry))
+    }
+}
 
+impl<V: SerializableValue> ForwardDictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    ///
+    /// cedarwood does not expose the arrays of its trie,
+    /// so the normalized patterns are serialized and the trie is rebuilt when loading.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_dictionary(
+            DictionaryKind::CedarwoodForward,
+            &self.patterns,
+            &self.entries,
+            self.normalization,
+            &self.values,
+        )
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let (patterns, entries, normalization, values) = deserialize_dictionary(
+            DictionaryKind::CedarwoodForward,
+            bytes,
+        )?;
+        let dat = create_forward_dat(&patterns)?;
+
+        Ok(Self { dat, entries, patterns, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        Self::from_bytes(&read_file(path)?)
+    }
+}
+
+impl<V> BackwardDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
         let patterns = patterns_with_values
-            .iter()
-            .map(|(x, _)| x);
-        if !is_unique(patterns) {
-            return Err(UltraNLPError::new("The patterns are not unique"));
-        }
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect::<Vec<_>>();
+        let dat = create_backward_dat(&patterns)?;
+
+        Ok(Self { dat, entries, patterns, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
 
-        let dat = create_dat_with_values(patterns_with_values);
+    /// Inserts a pattern with its value,
+    /// or replaces the entry and the value of an existing pattern and returns the old ones.
+    pub fn insert_with_value<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+        value: V,
+    ) -> UltraNLPResult<Option<(PatternEntry, V)>> {
+        insert_pattern(
+            &mut self.dat,
+            &mut self.entries,
+            &mut self.patterns,
+            &mut self.values,
+            // 与分词时对文本的规范化保持一致.
+            self.normalization.normalize(pattern.as_ref()),
+            (entry, value),
+            backward_key,
+        )
+    }
 
-        Ok(Self { dat })
+    /// Removes a pattern and returns its entry and its value.
+    ///
+    /// The last pattern takes the index of the removed pattern,
+    /// see `RemovedPattern::moved_index_of_patterns`.
+    /// Use `BidirectionalDictionary` to keep the indexes of the forward and backward dictionaries consistent.
+    pub fn remove<T: AsRef<str>>(&mut self, pattern: T) -> Option<RemovedPattern<V>> {
+        remove_pattern(
+            &mut self.dat,
+            &mut self.entries,
+            &mut self.patterns,
+            &mut self.values,
+            // 与分词时对文本的规范化保持一致.
+            self.normalization.normalize(pattern.as_ref()),
+            backward_key,
+        )
     }
 }
 
@@ -41,94 +359,394 @@ impl BackwardDictionary {
     pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
         patterns: I
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
-        if patterns_with_values.len() == 0 {
-            return Err(UltraNLPError::new("The patterns cannot be empty"));
-        }
+        Self::new_with_entries(with_default_entries(patterns))
+    }
 
-        let patterns = patterns_with_values
-            .iter()
-            .map(|(x, _)| x);
-        if !is_unique(patterns) {
-            return Err(UltraNLPError::new("The patterns are not unique"));
-        }
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
 
-        let patterns_with_values = patterns_with_values
-            .into_iter()
-            .map(|(pattern, value)| {
-                let pattern = pattern
-                    .chars()
-                    .rev()
-                    .collect::<String>();
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
 
-                (pattern, value)
-            })
-            .collect::<Vec<_>>();
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
 
-        let dat = create_dat_with_values(patterns_with_values);
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
 
-        Ok(Self { dat })
+    /// Inserts a pattern, or replaces the entry of an existing pattern and returns the old entry.
+    pub fn insert<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+    ) -> UltraNLPResult<Option<PatternEntry>> {
+        self
+            .insert_with_value(pattern, entry, ())
+            .map(|replaced| replaced.map(|(entry, _)| entry))
     }
 }
 
-fn create_dat_with_values<
-    T: AsRef<str>,
-    I: IntoIterator<Item = (T, i32)>,
->(patterns_with_values: I) -> Cedar {
-    let key_values: Vec<(String, i32)> = patterns_with_values.into_iter()
-        .map(|(key, value)| {
-            let key = key.as_ref().to_owned();
-            let value = value;
-
-            (key, value)
-        })
+impl<V: SerializableValue> BackwardDictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    ///
+    /// cedarwood does not expose the arrays of its trie,
+    /// so the normalized patterns are serialized and the trie is rebuilt when loading.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_dictionary(
+            DictionaryKind::CedarwoodBackward,
+            &self.patterns,
+            &self.entries,
+            self.normalization,
+            &self.values,
+        )
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let (patterns, entries, normalization, values) = deserialize_dictionary(
+            DictionaryKind::CedarwoodBackward,
+            bytes,
+        )?;
+        let dat = create_backward_dat(&patterns)?;
+
+        Ok(Self { dat, entries, patterns, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        Self::from_bytes(&read_file(path)?)
+    }
+}
+
+impl<V> Dictionary for ForwardDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+impl<V> Dictionary for BackwardDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+fn create_forward_dat(patterns: &[String]) -> UltraNLPResult<Cedar> {
+    create_dat_with_values(patterns.iter().map(|pattern| forward_key(pattern)))
+}
+
+fn create_backward_dat(patterns: &[String]) -> UltraNLPResult<Cedar> {
+    create_dat_with_values(patterns.iter().map(|pattern| backward_key(pattern)))
+}
+
+fn forward_key(pattern: &str) -> String {
+    pattern.to_owned()
+}
+
+fn backward_key(pattern: &str) -> String {
+    pattern
+        .chars()
+        .rev()
+        .collect()
+}
+
+fn insert_pattern<V>(
+    dat: &mut Cedar,
+    entries: &mut PatternEntries,
+    patterns: &mut Vec<String>,
+    values: &mut Vec<V>,
+    pattern: String,
+    (entry, value): (PatternEntry, V),
+    key_of: fn(&str) -> String,
+) -> UltraNLPResult<Option<(PatternEntry, V)>> {
+    if pattern.is_empty() {
+        return Err(UltraNLPError::EmptyPattern);
+    }
+
+    let key = key_of(&pattern);
+    match dat.exact_match_search(&key) {
+        Some((id, _, _)) => {
+            let index = usize::try_from(id)
+                // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
+                .unwrap();
+
+            let old_entry = entries.replace(index, entry);
+            let old_value = std::mem::replace(&mut values[index], value);
+
+            Ok(Some((old_entry, old_value)))
+        },
+        None => {
+            let id = to_value(patterns.len())?;
+            dat.update(&key, id);
+
+            let pattern_chars = pattern.chars().count();
+            if pattern_chars > entries.max_pattern_chars() {
+                entries.set_max_pattern_chars(pattern_chars);
+            }
+            entries.push(entry);
+            patterns.push(pattern);
+            values.push(value);
+
+            Ok(None)
+        },
+    }
+}
+
+fn remove_pattern<V>(
+    dat: &mut Cedar,
+    entries: &mut PatternEntries,
+    patterns: &mut Vec<String>,
+    values: &mut Vec<V>,
+    pattern: String,
+    key_of: fn(&str) -> String,
+) -> Option<RemovedPattern<V>> {
+    let key = key_of(&pattern);
+    let (value, _, _) = dat.exact_match_search(&key)?;
+    let index = usize::try_from(value)
+        // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
+        .unwrap();
+
+    dat.erase(&key);
+    let entry = entries.swap_remove(index);
+    patterns.swap_remove(index);
+    let value = values.swap_remove(index);
+    let moved_index_of_patterns = patterns.get(index).map(|moved_pattern| {
+        // index小于原先的模式数量, 而模式数量可以被转换为i32.
+        dat.update(&key_of(moved_pattern), index as i32);
+
+        patterns.len()
+    });
+
+    if pattern.chars().count() == entries.max_pattern_chars() {
+        let max_pattern_chars = patterns
+            .iter()
+            .map(|pattern| pattern.chars().count())
+            .max()
+            .unwrap_or(0);
+        entries.set_max_pattern_chars(max_pattern_chars);
+    }
+
+    Some(RemovedPattern::new(entry, value, index, moved_index_of_patterns))
+}
+
+// Cedar以i32保存值.
+fn to_value(index_of_patterns: usize) -> UltraNLPResult<i32> {
+    i32::try_from(index_of_patterns)
+        .map_err(|_| UltraNLPError::TooManyPatterns { max_patterns: i32::MAX as usize })
+}
+
+// 模式的值为它在patterns中的位置.
+fn create_dat_with_values<I: IntoIterator<Item = String>>(
+    keys: I
+) -> UltraNLPResult<Cedar> {
+    let keys = keys
+        .into_iter()
         .collect::<Vec<_>>();
-    let key_values: Vec<(&str, i32)> = key_values
+    let key_values: Vec<(&str, i32)> = keys
         .iter()
-        .map(|(key, value)| (key.as_str(), *value))
-        .collect::<Vec<_>>();
+        .enumerate()
+        .map(|(index, key)| {
+            let value = to_value(index)?;
+
+            Ok((key.as_str(), value))
+        })
+        .collect::<UltraNLPResult<Vec<_>>>()?;
 
     let mut dat = Cedar::new();
     dat.build(&key_values);
 
-    dat
+    Ok(dat)
+}
+
+fn serialize_dictionary<V: SerializableValue>(
+    kind: DictionaryKind,
+    patterns: &[String],
+    entries: &PatternEntries,
+    normalization: Normalization,
+    values: &[V],
+) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder.write_entries(entries);
+    encoder.write_normalization(normalization);
+    encoder.write_values(values);
+    encoder.write_patterns(
+        patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| (pattern, index))
+    );
+
+    encode(kind, &encoder.into_bytes())
+}
+
+fn deserialize_dictionary<V: SerializableValue>(
+    kind: DictionaryKind,
+    bytes: &[u8],
+) -> UltraNLPResult<(Vec<String>, PatternEntries, Normalization, Vec<V>)> {
+    let mut decoder = Decoder::new(decode(kind, bytes)?);
+    let entries = decoder.read_entries()?;
+    let normalization = decoder.read_normalization()?;
+    let values = decoder.read_values()?;
+    if values.len() != entries.len() {
+        return Err(UltraNLPError::invalid_format("The number of values does not match"));
+    }
+    let patterns_with_values = decoder.read_patterns()?;
+    if !decoder.is_empty() {
+        return Err(UltraNLPError::invalid_format("The dictionary has unexpected trailing bytes"));
+    }
+
+    let patterns = patterns_with_values
+        .into_iter()
+        .enumerate()
+        .map(|(index, (pattern, value))| {
+            if value == index {
+                Ok(pattern.to_owned())
+            } else {
+                Err(UltraNLPError::invalid_format("The values of the patterns are not in order"))
+            }
+        })
+        .collect::<UltraNLPResult<Vec<_>>>()?;
+    if patterns.len() != entries.len() {
+        return Err(UltraNLPError::invalid_format("The numbers of patterns and entries do not match"));
+    }
+    validate_max_pattern_chars(&patterns, &entries)?;
+
+    Ok((patterns, entries, normalization, values))
 }
 
 fn prepare_patterns_for_dictionary<
     T: AsRef<str>,
-    I: IntoIterator<Item = T>
+    I: IntoIterator<Item = (T, PatternEntry)>
 >(
-    patterns: I,
-) -> UltraNLPResult<Vec<(String, i32)>> {
-    let patterns_with_values = patterns
+    patterns_with_entries: I,
+    normalization: Normalization,
+) -> UltraNLPResult<(Vec<(String, i32)>, PatternEntries)> {
+    let (patterns_with_values, entries): (Vec<_>, Vec<_>) = patterns_with_entries
         .into_iter()
         .enumerate()
-        .map(|(index, pattern)| -> Result<(String, i32), _>{
-            let pattern = pattern.as_ref().to_lowercase();
+        .map(|(index, (pattern, entry))| -> Result<_, _> {
+            // 与分词时对文本的规范化保持一致.
+            let pattern = normalization.normalize(pattern.as_ref());
 
-            let value = i32::try_from(index)
-                .map_err(|err| UltraNLPError::new(err.to_string()))?;
+            let value = to_value(index)?;
 
-            Ok((pattern, value))
+            Ok(((pattern, value), entry))
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<UltraNLPResult<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    validate_patterns(
+        patterns_with_values
+            .iter()
+            .map(|(pattern, _)| pattern.as_str())
+    )?;
 
-    Ok(patterns_with_values)
-}
+    let max_pattern_chars = patterns_with_values
+        .iter()
+        .map(|(pattern, _)| pattern.chars().count())
+        .max()
+        .unwrap_or(0);
 
-fn is_unique<T: AsRef<str>, I: IntoIterator<Item = T>>(
-    collection: I
-) -> bool {
-    let mut set = HashSet::new();
-    collection
-        .into_iter()
-        .all(|x| set.insert(x.as_ref().to_owned()))
+    Ok((patterns_with_values, PatternEntries::new(entries, max_pattern_chars)))
 }
 
 #[cfg(test)]
 mod tests {
     mod forward_dictionary {
-        use crate::cedarwood::ForwardDictionary;
+        use crate::{BehaviorForUnmatched, Normalization, PatternEntry};
+        use crate::cedarwood::{segment_forward_longest, ForwardDictionary};
 
         #[test]
         fn test_empty_patterns() {
@@ -150,10 +768,138 @@ mod tests {
 
             assert!(ForwardDictionary::new(patterns).is_err());
         }
+
+        #[test]
+        fn test_patterns_with_frequencies() {
+            let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+            let dict = ForwardDictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+            assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+            assert_eq!(dict.entry(1).unwrap().tag(), None);
+            assert_eq!(dict.total_frequency(), 30);
+        }
+
+        #[test]
+        fn test_patterns_with_tagged_frequencies() {
+            let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+            let dict = ForwardDictionary::new_with_tagged_frequencies(
+                patterns_with_tagged_frequencies
+            ).unwrap();
+
+            assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+            assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+            assert!(dict.entry(2).is_none());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes() {
+            let text = "商品和服务";
+            let dict = ForwardDictionary::new_with_tagged_frequencies(
+                vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = <ForwardDictionary>::from_bytes(&bytes).unwrap();
+
+            assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(result.total_frequency(), 60);
+            assert_eq!(result.max_pattern_chars(), 2);
+            assert_eq!(
+                segment_forward_longest(text, &result, BehaviorForUnmatched::KeepAsWords),
+                segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            );
+        }
+
+        #[test]
+        fn test_from_corrupted_bytes() {
+            let dict = ForwardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+            let mut bytes = dict.to_bytes();
+            let index = bytes.len() / 2;
+            bytes[index] ^= 1;
+
+            assert!(<ForwardDictionary>::from_bytes(&bytes).is_err());
+        }
+
+        #[test]
+        fn test_insert_with_value_and_remove() {
+            let text = "商品和服务";
+            let mut dict = ForwardDictionary::new_with_values(
+                vec![
+                    ("商品", PatternEntry::default(), 1),
+                    ("和服", PatternEntry::default(), 2),
+                ],
+                Normalization::default(),
+            ).unwrap();
+
+            let inserted = dict.insert_with_value("服务", PatternEntry::default(), 3).unwrap();
+            let replaced = dict.insert_with_value("商品", PatternEntry::default(), 10).unwrap();
+            let removed = dict.remove("和服").map(|x| x.into_entry_and_value());
+
+            assert!(inserted.is_none());
+            assert_eq!(replaced, Some((PatternEntry::default(), 1)));
+            assert_eq!(removed, Some((PatternEntry::default(), 2)));
+            assert_eq!(
+                segment_forward_longest(text, &dict, BehaviorForUnmatched::Ignore)
+                    .into_iter()
+                    .map(|x| x.value(&dict).copied())
+                    .collect::<Vec<_>>(),
+                vec![Some(10), Some(3)]
+            );
+        }
+
+        #[test]
+        fn test_insert() {
+            let text = "商品服务";
+            let mut dict = ForwardDictionary::new(vec!["商品", "和服"]).unwrap();
+
+            let result = dict.insert("服务", PatternEntry::new(30, Some("vn"))).unwrap();
+            let replaced = dict.insert("商品", PatternEntry::new(10, Some("n"))).unwrap();
+
+            assert!(result.is_none());
+            assert_eq!(replaced, Some(PatternEntry::default()));
+            assert!(dict.insert("", PatternEntry::default()).is_err());
+            assert_eq!(dict.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(dict.total_frequency(), 41);
+            assert_eq!(
+                segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+                    .into_iter()
+                    .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                    .collect::<Vec<_>>(),
+                vec![("商品", Some(0)), ("服务", Some(2))]
+            );
+        }
+
+        #[test]
+        fn test_remove() {
+            let text = "商品和服务";
+            let mut dict = ForwardDictionary::new_with_frequencies(
+                vec![("商品", 10), ("和服", 20), ("服务", 30)]
+            ).unwrap();
+
+            let result = dict.remove("和服").unwrap();
+
+            assert_eq!(result.entry(), &PatternEntry::new::<&str>(20, None));
+            assert_eq!(result.index_of_patterns(), 1);
+            assert_eq!(result.moved_index_of_patterns(), Some(2));
+            assert!(dict.remove("和服").is_none());
+            assert_eq!(dict.entry(1).unwrap().frequency(), 30);
+            assert!(dict.entry(2).is_none());
+            assert_eq!(dict.total_frequency(), 40);
+            assert_eq!(
+                segment_forward_longest(text, &dict, BehaviorForUnmatched::Ignore)
+                    .into_iter()
+                    .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                    .collect::<Vec<_>>(),
+                vec![("商品", Some(0)), ("服务", Some(1))]
+            );
+        }
     }
 
     mod backward_dictionary {
-        use crate::cedarwood::BackwardDictionary;
+        use crate::{BehaviorForUnmatched, PatternEntry};
+        use crate::cedarwood::{segment_backward_longest, BackwardDictionary};
 
         #[test]
         fn test_empty_patterns() {
@@ -175,5 +921,105 @@ mod tests {
 
             assert!(BackwardDictionary::new(patterns).is_err());
         }
+
+        #[test]
+        fn test_patterns_with_frequencies() {
+            let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+            let dict = BackwardDictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+            assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+            assert_eq!(dict.entry(1).unwrap().tag(), None);
+            assert_eq!(dict.total_frequency(), 30);
+        }
+
+        #[test]
+        fn test_patterns_with_tagged_frequencies() {
+            let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+            let dict = BackwardDictionary::new_with_tagged_frequencies(
+                patterns_with_tagged_frequencies
+            ).unwrap();
+
+            assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+            assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+            assert!(dict.entry(2).is_none());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes() {
+            let text = "商品和服务";
+            let dict = BackwardDictionary::new_with_tagged_frequencies(
+                vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = <BackwardDictionary>::from_bytes(&bytes).unwrap();
+
+            assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(result.total_frequency(), 60);
+            assert_eq!(result.max_pattern_chars(), 2);
+            assert_eq!(
+                segment_backward_longest(text, &result, BehaviorForUnmatched::KeepAsWords),
+                segment_backward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            );
+        }
+
+        #[test]
+        fn test_from_corrupted_bytes() {
+            let dict = BackwardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+            let mut bytes = dict.to_bytes();
+            let index = bytes.len() / 2;
+            bytes[index] ^= 1;
+
+            assert!(<BackwardDictionary>::from_bytes(&bytes).is_err());
+        }
+
+        #[test]
+        fn test_insert() {
+            let text = "商品服务";
+            let mut dict = BackwardDictionary::new(vec!["商品", "和服"]).unwrap();
+
+            let result = dict.insert("服务", PatternEntry::new(30, Some("vn"))).unwrap();
+            let replaced = dict.insert("商品", PatternEntry::new(10, Some("n"))).unwrap();
+
+            assert!(result.is_none());
+            assert_eq!(replaced, Some(PatternEntry::default()));
+            assert!(dict.insert("", PatternEntry::default()).is_err());
+            assert_eq!(dict.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(dict.total_frequency(), 41);
+            assert_eq!(
+                segment_backward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+                    .into_iter()
+                    .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                    .collect::<Vec<_>>(),
+                vec![("商品", Some(0)), ("服务", Some(2))]
+            );
+        }
+
+        #[test]
+        fn test_remove() {
+            let text = "商品和服务";
+            let mut dict = BackwardDictionary::new_with_frequencies(
+                vec![("商品", 10), ("和服", 20), ("服务", 30)]
+            ).unwrap();
+
+            let result = dict.remove("和服").unwrap();
+
+            assert_eq!(result.entry(), &PatternEntry::new::<&str>(20, None));
+            assert_eq!(result.index_of_patterns(), 1);
+            assert_eq!(result.moved_index_of_patterns(), Some(2));
+            assert!(dict.remove("和服").is_none());
+            assert_eq!(dict.entry(1).unwrap().frequency(), 30);
+            assert!(dict.entry(2).is_none());
+            assert_eq!(dict.total_frequency(), 40);
+            assert_eq!(
+                segment_backward_longest(text, &dict, BehaviorForUnmatched::Ignore)
+                    .into_iter()
+                    .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                    .collect::<Vec<_>>(),
+                vec![("商品", Some(0)), ("服务", Some(1))]
+            );
+        }
     }
 }
@@ -1,21 +1,25 @@
 use crate::utils::split_as_char_ranges;
+use crate::normalized_text::NormalizedText;
+use crate::hmm::recognize_unmatched_words;
 use crate::{
     Match,
     TextRange,
     BehaviorForUnmatched,
-    UltraNLPError,
 };
 use crate::cedarwood::BackwardDictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_backward_longest<T: AsRef<str>>(
+/// Segments the text by backward longest matching.
+///
+/// There is no lazy version, because backward matching starts from the end of the text
+/// and cannot emit the words in the order of the text.
+pub fn segment_backward_longest<T: AsRef<str>, V>(
     text: T,
-    dict: &BackwardDictionary,
+    dict: &BackwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text
-        .as_ref()
-        .to_lowercase()
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text
+        .as_str()
         .chars()
         .rev()
         .collect::<String>();
@@ -51,7 +55,6 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                         text.len() - start_index,
                     );
                     let value = usize::try_from(id)
-                        .map_err(|err| UltraNLPError::new(err.to_string()))
                         // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
                         .unwrap();
 
@@ -67,8 +70,9 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                 let mut unmatched_results: Vec<Match> = vec![];
 
                 match behavior_for_unmatched {
-                    BehaviorForUnmatched::KeepAsWords => {
-                        if matched_results.len() > 0 {
+                    | BehaviorForUnmatched::KeepAsWords
+                    | BehaviorForUnmatched::KeepAsHmmWords(_) => {
+                        if !matched_results.is_empty() {
                             // 将之前未消耗的word作为Match提交
                             if let Some(index) = unconsumed_start_index {
                                 let result = Match::new(
@@ -81,16 +85,13 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                                 unmatched_results.push(result);
                                 unconsumed_start_index = None;
                             }
-                        } else {
-                            if start_index >= maximum_matched_end_index {
-                                if let None = unconsumed_start_index {
-                                    unconsumed_start_index = Some(start_index);
-                                }
-                            }
+                        } else if start_index >= maximum_matched_end_index
+                            && unconsumed_start_index.is_none() {
+                            unconsumed_start_index = Some(start_index);
                         }
                     },
                     BehaviorForUnmatched::KeepAsChars => {
-                        if matched_results.len() > 0 {
+                        if !matched_results.is_empty() {
                             // 将之前未消耗的char作为Match提交
                             if let Some(index) = unconsumed_start_index {
                                 let iter = split_as_char_ranges(&text[index..start_index])
@@ -107,12 +108,9 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                                 unmatched_results.extend(iter);
                                 unconsumed_start_index = None;
                             }
-                        } else {
-                            if start_index >= maximum_matched_end_index {
-                                if let None = unconsumed_start_index {
-                                    unconsumed_start_index = Some(start_index);
-                                }
-                            }
+                        } else if start_index >= maximum_matched_end_index
+                            && unconsumed_start_index.is_none() {
+                            unconsumed_start_index = Some(start_index);
                         }
                     },
                     BehaviorForUnmatched::Ignore => (),
@@ -132,7 +130,8 @@ pub fn segment_backward_longest<T: AsRef<str>>(
     if maximum_matched_end_index < text.len() {
         // 处理text剩余的文本
         match behavior_for_unmatched {
-            BehaviorForUnmatched::KeepAsWords => {
+            | BehaviorForUnmatched::KeepAsWords
+            | BehaviorForUnmatched::KeepAsHmmWords(_) => {
                 results.push(Match::new(
                     TextRange::new(
                         0,
@@ -161,7 +160,10 @@ pub fn segment_backward_longest<T: AsRef<str>>(
 
     results.reverse();
 
-    results
+    recognize_unmatched_words(results, normalized_text.as_str(), &behavior_for_unmatched)
+        .into_iter()
+        .map(|mat| normalized_text.to_original_match(mat))
+        .collect()
 }
 
 #[cfg(test)]
@@ -311,4 +313,26 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = BackwardDictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_backward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
 }
@@ -9,17 +9,20 @@ use crate::cedarwood::{
     ForwardDictionary,
 };
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_bidirectional_longest<T: AsRef<str>>(
+/// Segments the text by both forward and backward longest matching,
+/// and returns the results with fewer words, or fewer single chars if they are the same.
+///
+/// There is no lazy version, because the whole results of both directions must be compared.
+pub fn segment_bidirectional_longest<T: AsRef<str>, V>(
     text: T,
-    forward_dict: &ForwardDictionary,
-    backward_dict: &BackwardDictionary,
+    forward_dict: &ForwardDictionary<V>,
+    backward_dict: &BackwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
     let forward_results = segment_forward_longest(
         &text,
         forward_dict,
-        behavior_for_unmatched,
+        behavior_for_unmatched.clone(),
     );
     let backward_results = segment_backward_longest(
         &text,
@@ -49,9 +52,9 @@ pub fn segment_bidirectional_longest<T: AsRef<str>>(
     }
 }
 
-fn count_single_chars<T: AsRef<str>>(matches: &Vec<Match>, text: T) -> usize {
+fn count_single_chars<T: AsRef<str>>(matches: &[Match], text: T) -> usize {
     matches
-        .into_iter()
+        .iter()
         .map(|mat| {
             if mat.range().extract(text.as_ref())
                 .map(|text| text.chars().count() == 1)
@@ -61,13 +64,14 @@ fn count_single_chars<T: AsRef<str>>(matches: &Vec<Match>, text: T) -> usize {
                 0
             }
         })
-        .fold(0, |acc, cur| acc + cur)
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BehaviorForUnmatched;
+    use crate::{BehaviorForUnmatched, PatternEntry};
     use crate::cedarwood::{
+        segment_backward_longest,
         segment_bidirectional_longest,
         BackwardDictionary,
         ForwardDictionary,
@@ -316,4 +320,44 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let text = "当下雨天";
+        let patterns = vec!["当", "当下", "雨天", "积水"];
+        let mut forward_dict = ForwardDictionary::new(
+            patterns.clone()
+        ).unwrap();
+        let mut backward_dict = BackwardDictionary::new(
+            patterns.clone()
+        ).unwrap();
+
+        // 对两个词典进行相同的操作, 模式的索引保持一致.
+        forward_dict.remove("当");
+        backward_dict.remove("当");
+        forward_dict.insert("下雨天", PatternEntry::default()).unwrap();
+        backward_dict.insert("下雨天", PatternEntry::default()).unwrap();
+
+        let result = segment_bidirectional_longest(
+            text,
+            &forward_dict,
+            &backward_dict,
+            BehaviorForUnmatched::KeepAsChars,
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("当下", Some(1)), ("雨天", Some(2))]
+        );
+        assert_eq!(
+            segment_backward_longest(text, &backward_dict, BehaviorForUnmatched::KeepAsChars)
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("当", None), ("下雨天", Some(3))]
+        );
+    }
 }
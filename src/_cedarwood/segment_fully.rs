@@ -1,129 +1,95 @@
-use crate::utils::split_as_char_ranges;
+use std::collections::VecDeque;
+use std::sync::Arc;
 use crate::{
     Match,
     TextRange,
-    BehaviorForUnmatched, UltraNLPError,
+    BehaviorForUnmatched,
+    normalized_text::NormalizedText,
+    segments::{MatchSource, Segments},
 };
 use crate::cedarwood::ForwardDictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_fully<T: AsRef<str>>(
+pub fn segment_fully<T: AsRef<str>, V>(
     text: T,
-    dict: &ForwardDictionary,
+    dict: &ForwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text.as_ref().to_lowercase();
-    let mut results: Vec<Match> = vec![];
-
-    let mut unconsumed_word_start_index: Option<usize> = None;
-    let mut unconsumed_char_start_index: Option<usize> = None;
-    let mut maximum_matched_end_index = 0;
-    (0..text.len()).for_each(|start_index| {
-        if text.is_char_boundary(start_index) {
-            let mut matched_results: Vec<Match> = vec![];
+    segment_fully_iter(text, dict, behavior_for_unmatched).collect()
+}
+
+/// Segments the text into all matched words lazily.
+pub fn segment_fully_iter<'a, T: AsRef<str>, V>(
+    text: T,
+    dict: &'a ForwardDictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> FullyIterator<'a, V> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let source = FullyMatches {
+        dict,
+        start_index: 0,
+        matches: VecDeque::new(),
+    };
+
+    FullyIterator {
+        segments: Segments::new(
+            source,
+            Arc::new(normalized_text),
+            behavior_for_unmatched,
+        ),
+    }
+}
+
+pub struct FullyIterator<'a, V> {
+    segments: Segments<FullyMatches<'a, V>>,
+}
+
+impl<V> Iterator for FullyIterator<'_, V> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+}
+
+struct FullyMatches<'a, V> {
+    dict: &'a ForwardDictionary<V>,
+    start_index: usize,
+    // 以上一个起点开始的, 尚未返回的匹配结果.
+    matches: VecDeque<Match>,
+}
+
+impl<V> MatchSource for FullyMatches<'_, V> {
+    fn next_match(&mut self, text: &str) -> Option<Match> {
+        while self.matches.is_empty() && self.start_index < text.len() {
+            let start_index = self.start_index;
+
             // 注意, 虽然不知道这个Option的意义, 但Option是Some不代表matches非空.
-            if let Some(matches) = dict.dat.common_prefix_search(&text[start_index..]) {
-                matches
+            if let Some(matches) = self.dict.dat.common_prefix_search(&text[start_index..]) {
+                let iter = matches
                     .into_iter()
-                    .for_each(|(id, length)| {
-                        let range = TextRange::new(
-                            start_index,
-                            start_index + length + 1
-                        );
+                    .map(|(id, length)| {
                         let value = usize::try_from(id)
-                            .map_err(|err| UltraNLPError::new(err.to_string()))
                             // 没有使用负数值, 且usize的最大值大于i32, 转换应当总是能成功
                             .unwrap();
 
-                        let result = Match::new(range, Some(value));
-                        matched_results.push(result);
-
-                        if range.end_index() > maximum_matched_end_index {
-                            maximum_matched_end_index = range.end_index();
-                        }
-                    });
-            }
-
-            let mut unmatched_results: Vec<Match> = {
-                let mut unmatched_results = vec![];
-
-                match behavior_for_unmatched {
-                    BehaviorForUnmatched::KeepAsWords => {
-                        if matched_results.len() > 0 {
-                            // 将之前未消耗的word作为Match提交
-                            if let Some(index) = unconsumed_word_start_index {
-                                let result = Match::new(
-                                    TextRange::new(index, start_index),
-                                    None,
-                                );
-                                unmatched_results.push(result);
-                                unconsumed_word_start_index = None;
-                            }
-                        } else {
-                            if start_index >= maximum_matched_end_index {
-                                if let None = unconsumed_word_start_index {
-                                    unconsumed_word_start_index = Some(start_index);
-                                }
-                            }
-                        }
-                    },
-                    BehaviorForUnmatched::KeepAsChars => {
-                        if matched_results.len() > 0{
-                            // 将之前未消耗的char作为Match提交
-                            if let Some(index) = unconsumed_char_start_index {
-                                let result = Match::new(
-                                    TextRange::new(index, start_index),
-                                    None,
-                                );
-                                results.push(result);
-                                unconsumed_char_start_index = None;
-                            }
-                        } else {
-                            if start_index >= maximum_matched_end_index {
-                                if let None = unconsumed_char_start_index {
-                                    unconsumed_char_start_index = Some(start_index);
-                                }
-                            }
-                        }
-                    },
-                    BehaviorForUnmatched::Ignore => (),
-                }
-
-                unmatched_results
-            };
-
-            results.append(&mut unmatched_results);
-            results.append(&mut matched_results);
-        }
-    });
-    if maximum_matched_end_index < text.len() {
-        // 处理text剩余的文本
-        match behavior_for_unmatched {
-            BehaviorForUnmatched::KeepAsWords => {
-                results.push(Match::new(
-                    TextRange::new(maximum_matched_end_index, text.len()),
-                    None
-                ))
-            },
-            BehaviorForUnmatched::KeepAsChars => {
-                let iter = split_as_char_ranges(&text[maximum_matched_end_index..])
-                    .map(|range| {
                         Match::new(
-                            TextRange::new(
-                                maximum_matched_end_index + range.start_index(),
-                                maximum_matched_end_index + range.end_index(),
-                            ),
-                            None
+                            TextRange::new(start_index, start_index + length + 1),
+                            Some(value),
                         )
                     });
 
-                results.extend(iter);
+                self.matches.extend(iter);
             }
-            BehaviorForUnmatched::Ignore => (),
+
+            self.start_index += text[start_index..]
+                .chars()
+                .next()
+                .map(|char| char.len_utf8())
+                .unwrap_or(1);
         }
-    }
 
-    results
+        self.matches.pop_front()
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +97,7 @@ mod tests {
     use crate::BehaviorForUnmatched;
     use crate::cedarwood::{
         segment_fully,
+        segment_fully_iter,
         ForwardDictionary,
     };
 
@@ -285,4 +252,48 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = ForwardDictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_fully(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let text = "南京市长江大桥".repeat(100);
+        let dict = ForwardDictionary::new(
+            vec!["南京", "南京市", "市长", "长江", "大桥"]
+        ).unwrap();
+
+        let result = segment_fully_iter(
+            &text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .take(3)
+                .map(|x| x.range().extract(&text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["南京", "南京市", "市长"]
+        );
+    }
 }
@@ -0,0 +1,157 @@
+use crate::{
+    BehaviorForUnmatched,
+    Segmentation,
+    normalized_text::NormalizedText,
+    hmm::recognize_unmatched_words,
+    dag::apply_behavior_for_unmatched,
+};
+use crate::cedarwood::ForwardDictionary;
+
+/// Returns at most `n` segmentations with the maximum probabilities in descending order.
+pub fn segment_n_best<T: AsRef<str>, V>(
+    text: T,
+    dict: &ForwardDictionary<V>,
+    n: usize,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> Vec<Segmentation> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text.as_str();
+
+    let dag = dict.build_dag(text);
+
+    dag.n_best_max_probability_paths(&dict.entries, n)
+        .into_iter()
+        .map(|(path, score)| {
+            let results = apply_behavior_for_unmatched(path, &behavior_for_unmatched);
+            let matches = recognize_unmatched_words(results, text, &behavior_for_unmatched)
+                .into_iter()
+                .map(|mat| normalized_text.to_original_match(mat))
+                .collect();
+
+            Segmentation::new(matches, score)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BehaviorForUnmatched;
+    use crate::test_utils::create_dict;
+    use crate::cedarwood::{segment_n_best, ForwardDictionary};
+
+    #[test]
+    fn test_n_best() {
+        let text = "商品和服务";
+        let dict = create_dict();
+
+        let result = segment_n_best(
+            text,
+            &dict,
+            2,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| {
+                    x.matches()
+                        .iter()
+                        .map(|x| x.range().extract(text).unwrap())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["商品", "和", "服务"],
+                vec!["商品", "和服", "务"],
+            ]
+        );
+        assert!(result[0].score() > result[1].score());
+    }
+
+    #[test]
+    fn test_ignore_unmatched() {
+        let text = " 商品和服务, hello world ";
+        let dict = create_dict();
+
+        let result = segment_n_best(
+            text,
+            &dict,
+            1,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| {
+                    x.into_matches()
+                        .into_iter()
+                        .map(|x| x.range().extract(text).unwrap())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            vec![vec!["商品", "和", "服务"]]
+        );
+    }
+
+    #[test]
+    fn test_fewer_segmentations_than_n() {
+        let text = "和服";
+        let dict = create_dict();
+
+        let result = segment_n_best(
+            text,
+            &dict,
+            10,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_distinct_segmentations() {
+        let text = "商品和服务";
+        let dict = ForwardDictionary::new(vec!["商品", "和服", "服务", "和", "商", "品"]).unwrap();
+
+        for behavior_for_unmatched in [
+            BehaviorForUnmatched::Ignore,
+            BehaviorForUnmatched::KeepAsChars,
+            BehaviorForUnmatched::KeepAsWords,
+        ] {
+            let result = segment_n_best(text, &dict, 10, behavior_for_unmatched)
+                .into_iter()
+                .map(|x| {
+                    x.into_matches()
+                        .into_iter()
+                        .map(|x| x.range())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(result.len(), 4);
+            assert!(
+                result
+                    .iter()
+                    .enumerate()
+                    .all(|(i, x)| !result[..i].contains(x))
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero() {
+        let text = "商品和服务";
+        let dict = create_dict();
+
+        let result = segment_n_best(
+            text,
+            &dict,
+            0,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert!(result.is_empty());
+    }
+}
@@ -2,62 +2,378 @@ use daachorse::charwise::{
     CharwiseDoubleArrayAhoCorasick as DoubleArrayAhoCorasick,
     CharwiseDoubleArrayAhoCorasickBuilder as DoubleArrayAhoCorasickBuilder,
 };
+use std::path::Path;
 use daachorse::MatchKind;
+use crate::daachorse::BackendError;
 use crate::{
+    Dictionary,
+    Normalization,
+    PatternEntry,
     UltraNLPResult,
-    UltraNLPError
+    UltraNLPError,
+    dag::Dag,
+    dictionary::validate_patterns,
+    SerializableValue,
+    serialization::{
+        decode,
+        encode,
+        read_file,
+        write_file,
+        Decoder,
+        DictionaryKind,
+        Encoder,
+    },
+    pattern_entry::{
+        PatternEntries,
+        with_default_entries,
+        with_frequency_entries,
+        with_tagged_frequency_entries,
+    },
 };
 
 #[derive(Clone)]
-pub struct StandardDictionary {
+pub struct StandardDictionary<V = ()> {
     pub(crate) acdat: DoubleArrayAhoCorasick<usize>,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // 第i个值属于第i个模式.
+    pub(crate) values: Vec<V>,
 }
 
 #[derive(Clone)]
-pub struct ForwardDictionary {
+pub struct ForwardDictionary<V = ()> {
     pub(crate) acdat: DoubleArrayAhoCorasick<usize>,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // 第i个值属于第i个模式.
+    pub(crate) values: Vec<V>,
 }
 
 #[derive(Clone)]
-pub struct BackwardDictionary {
+pub struct BackwardDictionary<V = ()> {
     pub(crate) acdat: DoubleArrayAhoCorasick<usize>,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // 第i个值属于第i个模式.
+    pub(crate) values: Vec<V>,
 }
 
-impl StandardDictionary {
-    pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
-        patterns: I
+impl<V> StandardDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
 
         let acdat = create_acdat_with_values(
             patterns_with_values,
             MatchKind::Standard
         )?;
 
-        Ok(Self { acdat, })
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Builds the DAG of all words in the normalized text,
+    /// the chars where no word starts are filled as single char edges.
+    pub(crate) fn build_dag(&self, text: &str) -> Dag {
+        let mut dag = Dag::new(text);
+        self.acdat
+            .find_overlapping_iter(text)
+            .for_each(|mat| dag.add_edge(mat.start(), mat.end(), mat.value()));
+        dag.fill_unmatched_chars(text);
+
+        dag
     }
 }
 
-impl ForwardDictionary {
+impl StandardDictionary {
     pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
         patterns: I
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
+}
+
+impl<V: SerializableValue> StandardDictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_dictionary(
+            DictionaryKind::DaachorseStandard,
+            &self.acdat,
+            &self.entries,
+            self.normalization,
+            &self.values,
+        )
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    ///
+    /// # Safety
+    ///
+    /// The automaton is deserialized without validation,
+    /// the header and the checksum detect corrupted bytes, but not crafted bytes.
+    /// The bytes must come from `to_bytes` of the same type.
+    pub unsafe fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let (acdat, entries, normalization, values) = unsafe {
+            deserialize_dictionary(DictionaryKind::DaachorseStandard, bytes)?
+        };
+
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
+
+    /// Loads a dictionary saved by `save`.
+    ///
+    /// # Safety
+    ///
+    /// See `from_bytes`.
+    pub unsafe fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let bytes = read_file(path)?;
+
+        unsafe { Self::from_bytes(&bytes) }
+    }
+}
+
+impl<V> ForwardDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
 
         let acdat = create_acdat_with_values(
             patterns_with_values,
             MatchKind::LeftmostLongest
         )?;
 
-        Ok(Self { acdat })
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
     }
 }
 
-impl BackwardDictionary {
+impl ForwardDictionary {
     pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
         patterns: I
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
+}
+
+impl<V: SerializableValue> ForwardDictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_dictionary(
+            DictionaryKind::DaachorseForward,
+            &self.acdat,
+            &self.entries,
+            self.normalization,
+            &self.values,
+        )
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    ///
+    /// # Safety
+    ///
+    /// The automaton is deserialized without validation,
+    /// the header and the checksum detect corrupted bytes, but not crafted bytes.
+    /// The bytes must come from `to_bytes` of the same type.
+    pub unsafe fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let (acdat, entries, normalization, values) = unsafe {
+            deserialize_dictionary(DictionaryKind::DaachorseForward, bytes)?
+        };
+
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
+
+    /// Loads a dictionary saved by `save`.
+    ///
+    /// # Safety
+    ///
+    /// See `from_bytes`.
+    pub unsafe fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let bytes = read_file(path)?;
+
+        unsafe { Self::from_bytes(&bytes) }
+    }
+}
+
+impl<V> BackwardDictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
 
         let reversed_patterns_with_values = patterns_with_values
             .into_iter()
@@ -76,10 +392,275 @@ impl BackwardDictionary {
             MatchKind::LeftmostLongest
         )?;
 
-        Ok(Self { acdat })
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+}
+
+impl BackwardDictionary {
+    pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
+        patterns: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
+}
+
+impl<V: SerializableValue> BackwardDictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize_dictionary(
+            DictionaryKind::DaachorseBackward,
+            &self.acdat,
+            &self.entries,
+            self.normalization,
+            &self.values,
+        )
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    ///
+    /// # Safety
+    ///
+    /// The automaton is deserialized without validation,
+    /// the header and the checksum detect corrupted bytes, but not crafted bytes.
+    /// The bytes must come from `to_bytes` of the same type.
+    pub unsafe fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let (acdat, entries, normalization, values) = unsafe {
+            deserialize_dictionary(DictionaryKind::DaachorseBackward, bytes)?
+        };
+
+        Ok(Self { acdat, entries, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
+
+    /// Loads a dictionary saved by `save`.
+    ///
+    /// # Safety
+    ///
+    /// See `from_bytes`.
+    pub unsafe fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let bytes = read_file(path)?;
+
+        unsafe { Self::from_bytes(&bytes) }
     }
 }
 
+impl<V> Dictionary for StandardDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+impl<V> Dictionary for ForwardDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+impl<V> Dictionary for BackwardDictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
+    }
+}
+
+fn serialize_dictionary<V: SerializableValue>(
+    kind: DictionaryKind,
+    acdat: &DoubleArrayAhoCorasick<usize>,
+    entries: &PatternEntries,
+    normalization: Normalization,
+    values: &[V],
+) -> Vec<u8> {
+    let mut encoder = Encoder::new();
+    encoder.write_entries(entries);
+    encoder.write_normalization(normalization);
+    encoder.write_values(values);
+    encoder.write_bytes(&acdat.serialize());
+
+    encode(kind, &encoder.into_bytes())
+}
+
+unsafe fn deserialize_dictionary<V: SerializableValue>(
+    kind: DictionaryKind,
+    bytes: &[u8],
+) -> UltraNLPResult<(DoubleArrayAhoCorasick<usize>, PatternEntries, Normalization, Vec<V>)> {
+    let mut decoder = Decoder::new(decode(kind, bytes)?);
+    let entries = decoder.read_entries()?;
+    let normalization = decoder.read_normalization()?;
+    let values = decoder.read_values()?;
+    if values.len() != entries.len() {
+        return Err(UltraNLPError::invalid_format("The number of values does not match"));
+    }
+    let acdat_bytes = decoder.read_bytes()?;
+    if !decoder.is_empty() {
+        return Err(UltraNLPError::invalid_format("The dictionary has unexpected trailing bytes"));
+    }
+
+    // 由调用者保证acdat_bytes来自serialize.
+    // daachorse只能将自动机反序列化为自己持有的Vec, 无法直接使用bytes,
+    // 因此即使bytes来自内存映射的文件, 自动机也会被复制到堆中, 不能在进程间共享.
+    let (acdat, rest) = unsafe {
+        DoubleArrayAhoCorasick::<usize>::deserialize_unchecked(acdat_bytes)
+    };
+    if !rest.is_empty() {
+        return Err(UltraNLPError::invalid_format("The dictionary has unexpected trailing bytes"));
+    }
+
+    Ok((acdat, entries, normalization, values))
+}
+
 fn create_acdat_with_values<
     T: AsRef<str>,
     I: IntoIterator<Item = (T, usize)>
@@ -91,37 +672,60 @@ fn create_acdat_with_values<
         .match_kind(match_kind)
         .build_with_values(patterns_with_values);
 
-    acdat.map_err(|err| UltraNLPError::new(err.to_string()))
+    acdat.map_err(|err| UltraNLPError::backend("daachorse", BackendError::new(err)))
 }
 
 fn prepare_patterns_for_dictionary<
     T: AsRef<str>,
-    I: IntoIterator<Item = T>
+    I: IntoIterator<Item = (T, PatternEntry)>
 >(
-    patterns: I,
-) -> UltraNLPResult<Vec<(String, usize)>> {
-    let patterns_with_values = patterns
+    patterns_with_entries: I,
+    normalization: Normalization,
+) -> UltraNLPResult<(Vec<(String, usize)>, PatternEntries)> {
+    let (patterns_with_values, entries): (Vec<_>, Vec<_>) = patterns_with_entries
         .into_iter()
         .enumerate()
-        .map(|(i, pattern)| -> Result<(String, usize), _>{
-            let pattern = pattern
-                .as_ref()
-                .to_lowercase();
-
-            let value = usize::try_from(i)
-                .map_err(|err| UltraNLPError::new(err.to_string()))?;
+        .map(|(index, (pattern, entry))| {
+            // 与分词时对文本的规范化保持一致.
+            let pattern = normalization.normalize(pattern.as_ref());
 
-            Ok((pattern, value))
+            ((pattern, index), entry)
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .unzip();
+    validate_patterns(
+        patterns_with_values
+            .iter()
+            .map(|(pattern, _)| pattern.as_str())
+    )?;
+
+    // daachorse以u32保存模式的长度.
+    let max_length = u32::MAX as usize;
+    if let Some((pattern_index, (pattern, _))) = patterns_with_values
+        .iter()
+        .enumerate()
+        .find(|(_, (pattern, _))| pattern.len() > max_length)
+    {
+        return Err(UltraNLPError::PatternTooLong {
+            pattern_index,
+            length: pattern.len(),
+            max_length,
+        });
+    }
 
-    Ok(patterns_with_values)
+    let max_pattern_chars = patterns_with_values
+        .iter()
+        .map(|(pattern, _)| pattern.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    Ok((patterns_with_values, PatternEntries::new(entries, max_pattern_chars)))
 }
 
 #[cfg(test)]
 mod tests {
     mod standard_dictionary {
-        use crate::daachorse::StandardDictionary;
+        use crate::{BehaviorForUnmatched, Normalization, PatternEntry};
+        use crate::daachorse::{segment_fully, StandardDictionary};
 
         #[test]
         fn test_empty_patterns() {
@@ -143,10 +747,81 @@ mod tests {
 
             assert!(StandardDictionary::new(patterns).is_err());
         }
+
+        #[test]
+        fn test_patterns_with_frequencies() {
+            let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+            let dict = StandardDictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+            assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+            assert_eq!(dict.entry(1).unwrap().tag(), None);
+            assert_eq!(dict.total_frequency(), 30);
+        }
+
+        #[test]
+        fn test_patterns_with_tagged_frequencies() {
+            let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+            let dict = StandardDictionary::new_with_tagged_frequencies(
+                patterns_with_tagged_frequencies
+            ).unwrap();
+
+            assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+            assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+            assert!(dict.entry(2).is_none());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes_with_values() {
+            let dict = StandardDictionary::new_with_values(
+                vec![
+                    ("商品", PatternEntry::default(), "goods".to_string()),
+                    ("服务", PatternEntry::default(), "service".to_string()),
+                ],
+                Normalization::default(),
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = unsafe { StandardDictionary::<String>::from_bytes(&bytes) }.unwrap();
+
+            assert_eq!(result.value(1).map(String::as_str), Some("service"));
+            assert!(unsafe { StandardDictionary::<u32>::from_bytes(&bytes) }.is_err());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes() {
+            let text = "商品和服务";
+            let dict = StandardDictionary::new_with_tagged_frequencies(
+                vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = unsafe { <StandardDictionary>::from_bytes(&bytes) }.unwrap();
+
+            assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(result.total_frequency(), 60);
+            assert_eq!(result.max_pattern_chars(), 2);
+            assert_eq!(
+                segment_fully(text, &result, BehaviorForUnmatched::KeepAsWords),
+                segment_fully(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            );
+        }
+
+        #[test]
+        fn test_from_corrupted_bytes() {
+            let dict = StandardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+            let mut bytes = dict.to_bytes();
+            let index = bytes.len() / 2;
+            bytes[index] ^= 1;
+
+            assert!(unsafe { <StandardDictionary>::from_bytes(&bytes) }.is_err());
+        }
     }
 
     mod forward_dictionary {
-        use crate::daachorse::ForwardDictionary;
+        use crate::{BehaviorForUnmatched, Normalization, PatternEntry};
+        use crate::daachorse::{segment_forward_longest, ForwardDictionary};
 
         #[test]
         fn test_empty_patterns() {
@@ -168,10 +843,86 @@ mod tests {
 
             assert!(ForwardDictionary::new(patterns).is_err());
         }
+
+        #[test]
+        fn test_patterns_with_frequencies() {
+            let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+            let dict = ForwardDictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+            assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+            assert_eq!(dict.entry(1).unwrap().tag(), None);
+            assert_eq!(dict.total_frequency(), 30);
+        }
+
+        #[test]
+        fn test_patterns_with_tagged_frequencies() {
+            let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+            let dict = ForwardDictionary::new_with_tagged_frequencies(
+                patterns_with_tagged_frequencies
+            ).unwrap();
+
+            assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+            assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+            assert!(dict.entry(2).is_none());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes() {
+            let text = "商品和服务";
+            let dict = ForwardDictionary::new_with_tagged_frequencies(
+                vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = unsafe { <ForwardDictionary>::from_bytes(&bytes) }.unwrap();
+
+            assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(result.total_frequency(), 60);
+            assert_eq!(result.max_pattern_chars(), 2);
+            assert_eq!(
+                segment_forward_longest(text, &result, BehaviorForUnmatched::KeepAsWords),
+                segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            );
+        }
+
+        #[test]
+        fn test_from_corrupted_bytes() {
+            let dict = ForwardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+            let mut bytes = dict.to_bytes();
+            let index = bytes.len() / 2;
+            bytes[index] ^= 1;
+
+            assert!(unsafe { <ForwardDictionary>::from_bytes(&bytes) }.is_err());
+        }
+
+        #[test]
+        fn test_patterns_with_values() {
+            let text = "商品和服务";
+            let dict = ForwardDictionary::new_with_values(
+                vec![
+                    ("商品", PatternEntry::default(), "product"),
+                    ("和服", PatternEntry::default(), "kimono"),
+                    ("服务", PatternEntry::default(), "service"),
+                ],
+                Normalization::default(),
+            ).unwrap();
+
+            let result = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+                .into_iter()
+                .map(|x| x.value(&dict).copied())
+                .collect::<Vec<_>>();
+
+            assert_eq!(result, vec![Some("product"), Some("kimono"), None]);
+            assert_eq!(dict.value(2), Some(&"service"));
+            assert!(dict.value(3).is_none());
+        }
     }
 
     mod backward_dictionary {
-        use crate::daachorse::BackwardDictionary;
+        use crate::BehaviorForUnmatched;
+        use crate::daachorse::{segment_backward_longest, BackwardDictionary};
 
         #[test]
         fn test_empty_patterns() {
@@ -193,5 +944,58 @@ mod tests {
 
             assert!(BackwardDictionary::new(patterns).is_err());
         }
+
+        #[test]
+        fn test_patterns_with_frequencies() {
+            let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+            let dict = BackwardDictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+            assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+            assert_eq!(dict.entry(1).unwrap().tag(), None);
+            assert_eq!(dict.total_frequency(), 30);
+        }
+
+        #[test]
+        fn test_patterns_with_tagged_frequencies() {
+            let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+            let dict = BackwardDictionary::new_with_tagged_frequencies(
+                patterns_with_tagged_frequencies
+            ).unwrap();
+
+            assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+            assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+            assert!(dict.entry(2).is_none());
+        }
+
+        #[test]
+        fn test_to_bytes_and_from_bytes() {
+            let text = "商品和服务";
+            let dict = BackwardDictionary::new_with_tagged_frequencies(
+                vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+            ).unwrap();
+
+            let bytes = dict.to_bytes();
+            let result = unsafe { <BackwardDictionary>::from_bytes(&bytes) }.unwrap();
+
+            assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+            assert_eq!(result.total_frequency(), 60);
+            assert_eq!(result.max_pattern_chars(), 2);
+            assert_eq!(
+                segment_backward_longest(text, &result, BehaviorForUnmatched::KeepAsWords),
+                segment_backward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            );
+        }
+
+        #[test]
+        fn test_from_corrupted_bytes() {
+            let dict = BackwardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+            let mut bytes = dict.to_bytes();
+            let index = bytes.len() / 2;
+            bytes[index] ^= 1;
+
+            assert!(unsafe { <BackwardDictionary>::from_bytes(&bytes) }.is_err());
+        }
     }
 }
@@ -0,0 +1,30 @@
+use std::error::Error;
+use std::fmt;
+use daachorse::errors::DaachorseError;
+
+/// The error of daachorse in `UltraNLPError::Backend`,
+/// daachorse does not implement `std::error::Error` for its own error.
+#[derive(Debug)]
+pub struct BackendError(DaachorseError);
+
+impl BackendError {
+    pub(crate) fn new(err: DaachorseError) -> Self {
+        Self(err)
+    }
+
+    pub fn inner(&self) -> &DaachorseError {
+        &self.0
+    }
+
+    pub fn into_inner(self) -> DaachorseError {
+        self.0
+    }
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for BackendError {}
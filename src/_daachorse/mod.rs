@@ -1,11 +1,23 @@
 mod dictionary;
+mod error;
 mod segment_backward_longest;
 mod segment_bidirectional_longest;
+mod segment_for_search;
 mod segment_forward_longest;
 mod segment_fully;
+mod segment_max_probability;
+mod segment_n_best;
+mod segment_shortest_path;
+mod segmenter;
 
 pub use dictionary::*;
+pub use error::*;
 pub use segment_backward_longest::*;
 pub use segment_bidirectional_longest::*;
+pub use segment_for_search::*;
 pub use segment_forward_longest::*;
 pub use segment_fully::*;
+pub use segment_max_probability::*;
+pub use segment_n_best::*;
+pub use segment_shortest_path::*;
+pub use segmenter::*;
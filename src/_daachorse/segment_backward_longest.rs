@@ -3,18 +3,23 @@ use crate::{
     TextRange,
     BehaviorForUnmatched,
     utils::split_as_char_ranges,
+    normalized_text::NormalizedText,
+    hmm::recognize_unmatched_words,
 };
 use crate::daachorse::BackwardDictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_backward_longest<T: AsRef<str>>(
+/// Segments the text by backward longest matching.
+///
+/// There is no lazy version, because backward matching starts from the end of the text
+/// and cannot emit the words in the order of the text.
+pub fn segment_backward_longest<T: AsRef<str>, V>(
     text: T,
-    dict: &BackwardDictionary,
+    dict: &BackwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text
-        .as_ref()
-        .to_lowercase()
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text
+        .as_str()
         .chars()
         .rev()
         .collect::<String>();
@@ -42,7 +47,8 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                         // 处理匹配结果之前的文本
                         match behavior_for_unmatched {
                             BehaviorForUnmatched::Ignore => {},
-                            BehaviorForUnmatched::KeepAsWords => {
+                            | BehaviorForUnmatched::KeepAsWords
+                            | BehaviorForUnmatched::KeepAsHmmWords(_) => {
                                 let result = Match::new(
                                     TextRange::new(
                                         text.len() - (start_index + mat.start()),
@@ -77,7 +83,8 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                     // 处理text剩余的文本
                     match behavior_for_unmatched {
                         BehaviorForUnmatched::Ignore => {},
-                        BehaviorForUnmatched::KeepAsWords => {
+                        | BehaviorForUnmatched::KeepAsWords
+                        | BehaviorForUnmatched::KeepAsHmmWords(_) => {
                             results.push(
                                 Match::new(
                                     TextRange::new(
@@ -104,7 +111,7 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                         },
                     }
 
-                    start_index += 1;
+                    break;
                 }
             }
         } else {
@@ -114,7 +121,10 @@ pub fn segment_backward_longest<T: AsRef<str>>(
 
     results.reverse();
 
-    results
+    recognize_unmatched_words(results, normalized_text.as_str(), &behavior_for_unmatched)
+        .into_iter()
+        .map(|mat| normalized_text.to_original_match(mat))
+        .collect()
 }
 
 #[cfg(test)]
@@ -264,4 +274,26 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = BackwardDictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_backward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
 }
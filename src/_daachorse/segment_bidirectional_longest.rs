@@ -9,17 +9,20 @@ use crate::daachorse::{
     ForwardDictionary,
 };
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_bidirectional_longest<T: AsRef<str>>(
+/// Segments the text by both forward and backward longest matching,
+/// and returns the results with fewer words, or fewer single chars if they are the same.
+///
+/// There is no lazy version, because the whole results of both directions must be compared.
+pub fn segment_bidirectional_longest<T: AsRef<str>, V>(
     text: T,
-    forward_dict: &ForwardDictionary,
-    backward_dict: &BackwardDictionary,
+    forward_dict: &ForwardDictionary<V>,
+    backward_dict: &BackwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
     let forward_results = segment_forward_longest(
         &text,
         forward_dict,
-        behavior_for_unmatched,
+        behavior_for_unmatched.clone(),
     );
     let backward_results = segment_backward_longest(
         &text,
@@ -49,9 +52,9 @@ pub fn segment_bidirectional_longest<T: AsRef<str>>(
     }
 }
 
-fn count_single_chars<T: AsRef<str>>(matches: &Vec<Match>, text: T) -> usize {
+fn count_single_chars<T: AsRef<str>>(matches: &[Match], text: T) -> usize {
     matches
-        .into_iter()
+        .iter()
         .map(|mat| {
             if mat.range().extract(text.as_ref())
                 .map(|text| text.chars().count() == 1)
@@ -61,7 +64,7 @@ fn count_single_chars<T: AsRef<str>>(matches: &Vec<Match>, text: T) -> usize {
                 0
             }
         })
-        .fold(0, |acc, cur| acc + cur)
+        .sum()
 }
 
 #[cfg(test)]
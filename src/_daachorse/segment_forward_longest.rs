@@ -1,118 +1,98 @@
+use std::sync::Arc;
 use crate::{
     Match,
     TextRange,
     BehaviorForUnmatched,
-    utils::split_as_char_ranges,
+    normalized_text::NormalizedText,
+    segments::{MatchSource, Segments},
 };
 use crate::daachorse::ForwardDictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_forward_longest<T: AsRef<str>>(
+pub fn segment_forward_longest<T: AsRef<str>, V>(
     text: T,
-    dict: &ForwardDictionary,
+    dict: &ForwardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text.as_ref().to_lowercase();
-    let mut results: Vec<Match> = vec![];
-
-    let mut start_index = 0;
-    while start_index < text.len() {
-        if text.is_char_boundary(start_index) {
-            let mut iter = dict.acdat.leftmost_find_iter(&text[start_index..]);
-
-            match iter.next() {
-                Some(mat) => {
-                    let real_mat_start_index = start_index + mat.start();
-                    let real_mat_end_index = start_index + mat.end();
-                    let result = Match::new(
-                        TextRange::new(real_mat_start_index, real_mat_end_index),
-                        Some(mat.value())
-                    );
-
-                    if mat.start() > 0 {
-                        // 处理匹配结果之前的文本
-                        match behavior_for_unmatched {
-                            BehaviorForUnmatched::Ignore => {},
-                            BehaviorForUnmatched::KeepAsWords => {
-                                results.push(
-                                    Match::new(
-                                        TextRange::new(
-                                            start_index, start_index + mat.start()
-                                        ),
-                                        None
-                                    )
-                                );
-                            },
-                            BehaviorForUnmatched::KeepAsChars => {
-                                let iter = split_as_char_ranges(
-                                    &text[start_index..start_index + mat.start()]
-                                )
-                                    .map(|range| {
-                                        Match::new(
-                                            TextRange::new(
-                                                start_index + range.start_index(),
-                                                start_index + range.end_index(),
-                                            ),
-                                            None,
-                                        )
-                                    });
-
-                                results.extend(iter);
-                            },
-                        }
-                    }
-
-                    start_index = real_mat_end_index;
-
-                    results.push(result);
-                }
-                None => {
-                    // 处理text剩余的文本
-                    match behavior_for_unmatched {
-                        BehaviorForUnmatched::Ignore => {},
-                        BehaviorForUnmatched::KeepAsWords => {
-                            results.push(
-                                Match::new(
-                                    TextRange::new(
-                                        start_index,
-                                        text.len()
-                                    ),
-                                    None,
-                                )
-                            );
-                        },
-                        BehaviorForUnmatched::KeepAsChars => {
-                            let iter = split_as_char_ranges(&text[start_index..])
-                                .map(|range| {
-                                    Match::new(
-                                        TextRange::new(
-                                            start_index + range.start_index(),
-                                            start_index + range.end_index(),
-                                        ),
-                                        None,
-                                    )
-                                });
-
-                            results.extend(iter);
-                        },
-                    }
-
-                    break;
-                }
-            }
-        } else {
-            start_index += 1;
+    segment_forward_longest_iter(text, dict, behavior_for_unmatched).collect()
+}
+
+/// Segments the text by forward longest matching lazily.
+pub fn segment_forward_longest_iter<'a, T: AsRef<str>, V>(
+    text: T,
+    dict: &'a ForwardDictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> ForwardLongestIterator<'a, V> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let source = ForwardLongestMatches::new(dict);
+
+    ForwardLongestIterator {
+        segments: Segments::new(
+            source,
+            Arc::new(normalized_text),
+            behavior_for_unmatched,
+        ),
+    }
+}
+
+pub struct ForwardLongestIterator<'a, V> {
+    segments: Segments<ForwardLongestMatches<'a, V>>,
+}
+
+impl<V> Iterator for ForwardLongestIterator<'_, V> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+}
+
+pub(crate) struct ForwardLongestMatches<'a, V> {
+    dict: &'a ForwardDictionary<V>,
+    start_index: usize,
+}
+
+impl<'a, V> ForwardLongestMatches<'a, V> {
+    pub(crate) fn new(dict: &'a ForwardDictionary<V>) -> Self {
+        Self {
+            dict,
+            start_index: 0,
         }
     }
+}
 
-    results
+impl<V> MatchSource for ForwardLongestMatches<'_, V> {
+    fn next_match(&mut self, text: &str) -> Option<Match> {
+        if self.start_index >= text.len() {
+            return None;
+        }
+
+        match self.dict.acdat.leftmost_find_iter(&text[self.start_index..]).next() {
+            Some(mat) => {
+                let start_index = self.start_index + mat.start();
+                let end_index = self.start_index + mat.end();
+                self.start_index = end_index;
+
+                Some(Match::new(
+                    TextRange::new(start_index, end_index),
+                    Some(mat.value())
+                ))
+            },
+            None => {
+                self.start_index = text.len();
+
+                None
+            },
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BehaviorForUnmatched;
+    use std::sync::Arc;
+    use crate::{BehaviorForUnmatched, HmmModel};
     use crate::daachorse::{
         segment_forward_longest,
+        segment_forward_longest_iter,
         ForwardDictionary,
     };
 
@@ -254,4 +234,97 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = ForwardDictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
+
+    #[test]
+    fn test_final_sigma() {
+        let text = "ΣΊΣΥΦΟΣ";
+        let dict = ForwardDictionary::new(
+            vec!["σίσυφος"]
+        ).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("ΣΊΣΥΦΟΣ", Some(0))]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_hmm_words() {
+        let text = "他来到了网易杭研大厦";
+        let dict = ForwardDictionary::new(
+            vec!["来到", "网易"]
+        ).unwrap();
+        let model = HmmModel::train(vec![
+            vec!["我", "来到", "北京", "清华大学"],
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"],
+            vec!["小明", "硕士", "毕业", "于", "中国", "科学院"],
+        ]).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsHmmWords(Arc::new(model))
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"]
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let text = "商品和服务".repeat(100);
+        let dict = ForwardDictionary::new(
+            vec!["商品", "和服", "服务"]
+        ).unwrap();
+
+        let result = segment_forward_longest_iter(
+            &text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .take(4)
+                .map(|x| x.range().extract(&text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "和服", "务", "商品"]
+        );
+    }
 }
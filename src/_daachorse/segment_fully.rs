@@ -1,103 +1,73 @@
+use std::sync::Arc;
+use daachorse::charwise::iter::{FindOverlappingIterator, StrIterator};
 use crate::{
     Match,
     TextRange,
     BehaviorForUnmatched,
-    utils::split_as_char_ranges,
+    normalized_text::NormalizedText,
+    segments::{MatchSource, Segments},
 };
 use crate::daachorse::StandardDictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_fully<T: AsRef<str>>(
+pub fn segment_fully<T: AsRef<str>, V>(
     text: T,
-    dict: &StandardDictionary,
+    dict: &StandardDictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text.as_ref().to_lowercase();
-
-    match behavior_for_unmatched {
-        BehaviorForUnmatched::Ignore => {
-            dict.acdat
-                .find_overlapping_iter(&text)
-                .map(|mat| {
-                    Match::new(
-                        TextRange::new(mat.start(), mat.end()),
-                        Some(mat.value())
-                    )
-                })
-                .collect()
-        },
-        | BehaviorForUnmatched::KeepAsChars
-        | BehaviorForUnmatched::KeepAsWords => {
-            let mut results: Vec<Match> = vec![];
-
-            let mut maximum_matched_end_index = 0;
-            dict.acdat.find_overlapping_iter(&text)
-                .for_each(|mat| {
-                    if mat.start() > maximum_matched_end_index {
-                        // 处理匹配结果之前的文本
-                        match behavior_for_unmatched {
-                            BehaviorForUnmatched::Ignore => panic!("Rust is stupid."),
-                            BehaviorForUnmatched::KeepAsWords => {
-                                results.push(
-                                    Match::new(
-                                        TextRange::new(maximum_matched_end_index, mat.start()),
-                                        None
-                                    )
-                                );
-                            },
-                            BehaviorForUnmatched::KeepAsChars => {
-                                let iter = split_as_char_ranges(
-                                    &text[maximum_matched_end_index..mat.start()]
-                                )
-                                    .map(|range| Match::new(range, None));
-
-                                results.extend(iter);
-                            },
-                        }
-
-                        // mat.end() > last_match_end_index
-                        maximum_matched_end_index = mat.end();
-                    } else {
-                        if mat.end() > maximum_matched_end_index {
-                            maximum_matched_end_index = mat.end();
-                        }
-                    }
-
-                    let result = Match::new(
-                        TextRange::new(mat.start(), mat.end()),
-                        Some(mat.value())
-                    );
-                    results.push(result);
-                });
-            if maximum_matched_end_index < text.len() {
-                // 处理text剩余的文本
-                match behavior_for_unmatched {
-                    BehaviorForUnmatched::KeepAsWords => {
-                        results.push(Match::new(
-                            TextRange::new(maximum_matched_end_index, text.len()),
-                            None
-                        ))
-                    },
-                    BehaviorForUnmatched::KeepAsChars => {
-                        let iter = split_as_char_ranges(&text[maximum_matched_end_index..])
-                            .map(|range| {
-                                Match::new(
-                                    TextRange::new(
-                                        maximum_matched_end_index + range.start_index(),
-                                        maximum_matched_end_index + range.end_index(),
-                                    ),
-                                    None,
-                                )
-                            });
-
-                        results.extend(iter);
-                    },
-                    BehaviorForUnmatched::Ignore => panic!("Rust is stupid."),
-                }
-            }
-
-            results
-        }
+    segment_fully_iter(text, dict, behavior_for_unmatched).collect()
+}
+
+/// Segments the text into all matched words lazily.
+pub fn segment_fully_iter<'a, T: AsRef<str>, V>(
+    text: T,
+    dict: &'a StandardDictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> FullyIterator<'a> {
+    let normalized_text = Arc::new(NormalizedText::new(text.as_ref(), dict.normalization()));
+    let source = FullyMatches {
+        iter: dict.acdat.find_overlapping_iter(SharedText(normalized_text.clone())),
+    };
+
+    FullyIterator {
+        segments: Segments::new(source, normalized_text, behavior_for_unmatched),
+    }
+}
+
+pub struct FullyIterator<'a> {
+    segments: Segments<FullyMatches<'a>>,
+}
+
+impl Iterator for FullyIterator<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+}
+
+// daachorse的迭代器需要持有文本, 因此与Segments共享规范化文本.
+struct SharedText(Arc<NormalizedText>);
+
+impl AsRef<str> for SharedText {
+    fn as_ref(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+struct FullyMatches<'a> {
+    iter: FindOverlappingIterator<'a, StrIterator<SharedText>, usize>,
+}
+
+impl MatchSource for FullyMatches<'_> {
+    fn next_match(&mut self, _text: &str) -> Option<Match> {
+        self.iter
+            .next()
+            .map(|mat| {
+                Match::new(
+                    TextRange::new(mat.start(), mat.end()),
+                    Some(mat.value())
+                )
+            })
     }
 }
 
@@ -106,6 +76,7 @@ mod tests {
     use crate::BehaviorForUnmatched;
     use crate::daachorse::{
         segment_fully,
+        segment_fully_iter,
         StandardDictionary,
     };
 
@@ -260,4 +231,48 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = StandardDictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_fully(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let text = "南京市长江大桥".repeat(100);
+        let dict = StandardDictionary::new(
+            vec!["南京", "南京市", "市长", "长江", "大桥"]
+        ).unwrap();
+
+        let result = segment_fully_iter(
+            &text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .take(3)
+                .map(|x| x.range().extract(&text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["南京", "南京市", "市长"]
+        );
+    }
 }
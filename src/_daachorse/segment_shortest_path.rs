@@ -0,0 +1,172 @@
+use crate::{
+    Match,
+    BehaviorForUnmatched,
+    normalized_text::NormalizedText,
+    hmm::recognize_unmatched_words,
+    dag::apply_behavior_for_unmatched,
+};
+use crate::daachorse::StandardDictionary;
+
+// 需要整个文本的DAG才能确定最优路径, 因此无法惰性地返回结果.
+pub fn segment_shortest_path<T: AsRef<str>, V>(
+    text: T,
+    dict: &StandardDictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> Vec<Match> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text.as_str();
+
+    let dag = dict.build_dag(text);
+    let path = dag.shortest_path();
+
+    let results = apply_behavior_for_unmatched(path, &behavior_for_unmatched);
+
+    recognize_unmatched_words(results, text, &behavior_for_unmatched)
+        .into_iter()
+        .map(|mat| normalized_text.to_original_match(mat))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BehaviorForUnmatched;
+    use crate::daachorse::{
+        segment_shortest_path,
+        StandardDictionary,
+    };
+
+    #[test]
+    fn test_ignore_unmatched() {
+        let text = " 商品和服务, hello world ";
+        let dict = StandardDictionary::new(
+            vec!["商品", "和服", "服务", "你好世界"]
+        ).unwrap();
+
+        let result = segment_shortest_path(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "和服"]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_chars() {
+        let text = " 商品和服务, hello world ";
+        let dict = StandardDictionary::new(
+            vec!["商品", "和服", "服务", "你好世界"]
+        ).unwrap();
+
+        let result = segment_shortest_path(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                " ",
+                "商品",
+                "和服",
+                "务",
+                ",",
+                " ",
+                "h",
+                "e",
+                "l",
+                "l",
+                "o",
+                " ",
+                "w",
+                "o",
+                "r",
+                "l",
+                "d",
+                " ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_words() {
+        let text = " 商品和服务, hello world ";
+        let dict = StandardDictionary::new(
+            vec!["商品", "和服", "服务", "你好世界"]
+        ).unwrap();
+
+        let result = segment_shortest_path(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                " ",
+                "商品",
+                "和服",
+                "务, hello world ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value() {
+        let text = " 商品和服务, hello world ";
+        let dict = StandardDictionary::new(
+            vec!["商品", "和服", "服务", "你好世界"]
+        ).unwrap();
+
+        let result = segment_shortest_path(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.index_of_patterns().unwrap())
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_fewer_words_than_greedy_segmentation() {
+        // 正向最长匹配和逆向最长匹配的结果都是4个词.
+        let text = "abcdefgh";
+        let dict = StandardDictionary::new(
+            vec!["ab", "abc", "cdef", "fgh", "gh"]
+        ).unwrap();
+
+        let result = segment_shortest_path(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["ab", "cdef", "gh"]
+        );
+    }
+}
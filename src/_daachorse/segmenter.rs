@@ -0,0 +1,120 @@
+use crate::{
+    BehaviorForUnmatched,
+    Match,
+    Segmentation,
+    Segmenter,
+};
+use crate::daachorse::{
+    segment_backward_longest,
+    segment_bidirectional_longest,
+    segment_for_search,
+    segment_forward_longest,
+    segment_fully,
+    segment_max_probability,
+    segment_n_best,
+    segment_shortest_path,
+    BackwardDictionary,
+    ForwardDictionary,
+    StandardDictionary,
+};
+
+/// The `Segmenter` of the daachorse backend.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DaachorseSegmenter;
+
+impl<V> Segmenter<V> for DaachorseSegmenter {
+    type StandardDictionary = StandardDictionary<V>;
+    type ForwardDictionary = ForwardDictionary<V>;
+    type BackwardDictionary = BackwardDictionary<V>;
+
+    fn segment_fully<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_fully(text, dict, behavior_for_unmatched)
+    }
+
+    fn segment_forward_longest<T: AsRef<str>>(
+        text: T,
+        dict: &Self::ForwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_forward_longest(text, dict, behavior_for_unmatched)
+    }
+
+    fn segment_backward_longest<T: AsRef<str>>(
+        text: T,
+        dict: &Self::BackwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_backward_longest(text, dict, behavior_for_unmatched)
+    }
+
+    fn segment_bidirectional_longest<T: AsRef<str>>(
+        text: T,
+        forward_dict: &Self::ForwardDictionary,
+        backward_dict: &Self::BackwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_bidirectional_longest(text, forward_dict, backward_dict, behavior_for_unmatched)
+    }
+
+    fn segment_for_search<T: AsRef<str>>(
+        text: T,
+        forward_dict: &Self::ForwardDictionary,
+        standard_dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_for_search(text, forward_dict, standard_dict, behavior_for_unmatched)
+    }
+
+    fn segment_max_probability<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_max_probability(text, dict, behavior_for_unmatched)
+    }
+
+    fn segment_n_best<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        n: usize,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Segmentation> {
+        segment_n_best(text, dict, n, behavior_for_unmatched)
+    }
+
+    fn segment_shortest_path<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        segment_shortest_path(text, dict, behavior_for_unmatched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, Dictionary, Segmenter};
+    use crate::daachorse::DaachorseSegmenter;
+
+    fn segment<S: Segmenter>(text: &str, patterns: Vec<&str>) -> Vec<String> {
+        let dict = S::ForwardDictionary::new(patterns).unwrap();
+
+        S::segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            .into_iter()
+            .map(|x| x.range().extract(text).unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_generic_segmenter() {
+        let text = "商品和服务";
+
+        let result = segment::<DaachorseSegmenter>(text, vec!["商品", "和服", "服务"]);
+
+        assert_eq!(result, vec!["商品", "和服", "务"]);
+    }
+}
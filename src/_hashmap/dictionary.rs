@@ -1,67 +1,402 @@
 use std::collections::HashMap;
+use std::path::Path;
 use crate::{
+    Normalization,
+    PatternEntry,
+    RemovedPattern,
+    SerializableValue,
     UltraNLPResult,
     UltraNLPError,
+    dag::Dag,
+    dictionary::validate_patterns,
+    serialization::{
+        decode,
+        encode,
+        read_file,
+        validate_max_pattern_chars,
+        write_file,
+        Decoder,
+        DictionaryKind,
+        Encoder,
+    },
+    pattern_entry::{
+        PatternEntries,
+        with_default_entries,
+        with_frequency_entries,
+        with_tagged_frequency_entries,
+    },
 };
 
 #[derive(Clone)]
-pub struct Dictionary {
+pub struct Dictionary<V = ()> {
     pub(crate) map: HashMap<String, usize>,
+    pub(crate) entries: PatternEntries,
+    pub(crate) normalization: Normalization,
+    // 规范化后的模式, 模式在此的位置即为它的值.
+    patterns: Vec<String>,
+    // 第i个值属于第i个模式.
+    values: Vec<V>,
+}
+
+impl<V> Dictionary<V> {
+    pub fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        let (patterns_with_entries, values): (Vec<_>, Vec<_>) = patterns_with_entries_and_values
+            .into_iter()
+            .map(|(pattern, entry, value)| ((pattern, entry), value))
+            .unzip();
+        let (patterns_with_values, entries) = prepare_patterns_for_dictionary(
+            patterns_with_entries,
+            normalization,
+        )?;
+        let map = patterns_with_values
+            .iter()
+            .map(|(pattern, value)| (pattern.clone(), *value))
+            .collect::<HashMap<_, _>>();
+        let patterns = patterns_with_values
+            .into_iter()
+            .map(|(pattern, _)| pattern)
+            .collect();
+
+        Ok(Self { map, entries, patterns, normalization, values })
+    }
+
+    pub fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.values.get(index_of_patterns)
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.entries.total_frequency()
+    }
+
+    /// Returns the number of chars of the longest pattern.
+    pub fn max_pattern_chars(&self) -> usize {
+        self.entries.max_pattern_chars()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Inserts a pattern with its value,
+    /// or replaces the entry and the value of an existing pattern and returns the old ones.
+    pub fn insert_with_value<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+        value: V,
+    ) -> UltraNLPResult<Option<(PatternEntry, V)>> {
+        // 与分词时对文本的规范化保持一致.
+        let pattern = self.normalization.normalize(pattern.as_ref());
+        if pattern.is_empty() {
+            return Err(UltraNLPError::EmptyPattern);
+        }
+
+        match self.map.get(&pattern) {
+            Some(index) => {
+                let old_entry = self.entries.replace(*index, entry);
+                let old_value = std::mem::replace(&mut self.values[*index], value);
+
+                Ok(Some((old_entry, old_value)))
+            },
+            None => {
+                let pattern_chars = pattern.chars().count();
+                if pattern_chars > self.entries.max_pattern_chars() {
+                    self.entries.set_max_pattern_chars(pattern_chars);
+                }
+                self.map.insert(pattern.clone(), self.patterns.len());
+                self.entries.push(entry);
+                self.patterns.push(pattern);
+                self.values.push(value);
+
+                Ok(None)
+            },
+        }
+    }
+
+    /// Removes a pattern and returns its entry and its value.
+    ///
+    /// The last pattern takes the index of the removed pattern,
+    /// see `RemovedPattern::moved_index_of_patterns`.
+    pub fn remove<T: AsRef<str>>(&mut self, pattern: T) -> Option<RemovedPattern<V>> {
+        let pattern = self.normalization.normalize(pattern.as_ref());
+        let index = self.map.remove(&pattern)?;
+
+        let entry = self.entries.swap_remove(index);
+        self.patterns.swap_remove(index);
+        let value = self.values.swap_remove(index);
+        let moved_index_of_patterns = self.patterns.get(index).map(|moved_pattern| {
+            self.map.insert(moved_pattern.clone(), index);
+
+            self.patterns.len()
+        });
+
+        if pattern.chars().count() == self.entries.max_pattern_chars() {
+            let max_pattern_chars = self.patterns
+                .iter()
+                .map(|pattern| pattern.chars().count())
+                .max()
+                .unwrap_or(0);
+            self.entries.set_max_pattern_chars(max_pattern_chars);
+        }
+
+        Some(RemovedPattern::new(entry, value, index, moved_index_of_patterns))
+    }
+
+    /// Builds the DAG of all words in the normalized text,
+    /// the chars where no word starts are filled as single char edges.
+    pub(crate) fn build_dag(&self, text: &str) -> Dag {
+        let mut dag = Dag::new(text);
+        text
+            .char_indices()
+            .for_each(|(start_index, _)| {
+                ((start_index + 1)..=self.max_end_index(text, start_index))
+                    .filter(|end_index| text.is_char_boundary(*end_index))
+                    .for_each(|end_index| {
+                        if let Some(value) = self.map.get(&text[start_index..end_index]) {
+                            dag.add_edge(start_index, end_index, *value);
+                        }
+                    });
+            });
+        dag.fill_unmatched_chars(text);
+
+        dag
+    }
+
+    /// Returns the end index of the longest substring starting at `start_index`
+    /// which can be a pattern, longer substrings are not looked up.
+    pub(crate) fn max_end_index(&self, text: &str, start_index: usize) -> usize {
+        text[start_index..]
+            .char_indices()
+            .nth(self.max_pattern_chars())
+            .map(|(index, _)| start_index + index)
+            .unwrap_or(text.len())
+    }
+
+    /// Returns the start index of the longest substring ending at `end_index`
+    /// which can be a pattern, longer substrings are not looked up.
+    pub(crate) fn min_start_index(&self, text: &str, end_index: usize) -> usize {
+        text[..end_index]
+            .char_indices()
+            .rev()
+            .nth(self.max_pattern_chars().saturating_sub(1))
+            .map(|(index, _)| index)
+            .unwrap_or(0)
+    }
 }
 
 impl Dictionary {
     pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
         patterns: I
     ) -> UltraNLPResult<Self> {
-        let patterns_with_values = prepare_patterns_for_dictionary(patterns)?;
-        if patterns_with_values.len() == 0 {
-            return Err(UltraNLPError::new("The patterns cannot be empty"));
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    /// Creates a dictionary whose patterns and segmented texts are normalized by `normalization`.
+    pub fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, ())),
+            normalization,
+        )
+    }
+
+    /// Inserts a pattern, or replaces the entry of an existing pattern and returns the old entry.
+    pub fn insert<T: AsRef<str>>(
+        &mut self,
+        pattern: T,
+        entry: PatternEntry,
+    ) -> UltraNLPResult<Option<PatternEntry>> {
+        self
+            .insert_with_value(pattern, entry, ())
+            .map(|replaced| replaced.map(|(entry, _)| entry))
+    }
+}
+
+impl<V: SerializableValue> Dictionary<V> {
+    /// Serializes the dictionary with a versioned header and a checksum.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder.write_entries(&self.entries);
+        encoder.write_normalization(self.normalization);
+        encoder.write_values(&self.values);
+        encoder.write_patterns(
+            self.patterns
+                .iter()
+                .enumerate()
+                .map(|(value, pattern)| (pattern, value))
+        );
+
+        encode(DictionaryKind::Hashmap, &encoder.into_bytes())
+    }
+
+    /// Deserializes a dictionary serialized by `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let mut decoder = Decoder::new(decode(DictionaryKind::Hashmap, bytes)?);
+        let entries = decoder.read_entries()?;
+        let normalization = decoder.read_normalization()?;
+        let values = decoder.read_values()?;
+        let patterns_with_values = decoder.read_patterns()?;
+        if !decoder.is_empty() {
+            return Err(UltraNLPError::invalid_format("The dictionary has unexpected trailing bytes"));
+        }
+        if values.len() != entries.len() {
+            return Err(UltraNLPError::invalid_format("The number of values does not match"));
+        }
+        if patterns_with_values.len() != entries.len() {
+            return Err(UltraNLPError::invalid_format("The numbers of patterns and entries do not match"));
         }
 
-        let mut map: HashMap<String, usize> = HashMap::new();
-        patterns_with_values
+        // 模式按值的顺序保存.
+        let patterns = patterns_with_values
             .into_iter()
-            .try_for_each(|(pattern, value)| {
-                let result = map.insert(pattern, value);
-
-                if let Some(_) = result {
-                    Err(UltraNLPError::new("The patterns are not unique"))
+            .enumerate()
+            .map(|(index, (pattern, value))| {
+                if value == index {
+                    Ok(pattern.to_owned())
                 } else {
-                    Ok(())
+                    Err(UltraNLPError::invalid_format("The values of the patterns are not in order"))
                 }
-            })?;
+            })
+            .collect::<UltraNLPResult<Vec<_>>>()?;
+        let map = patterns
+            .iter()
+            .enumerate()
+            .map(|(value, pattern)| (pattern.clone(), value))
+            .collect::<HashMap<_, _>>();
+        if map.len() != patterns.len() {
+            return Err(UltraNLPError::invalid_format("The patterns are not unique"));
+        }
+        validate_max_pattern_chars(&patterns, &entries)?;
+
+        Ok(Self { map, entries, patterns, normalization, values })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        write_file(path, &self.to_bytes())
+    }
 
-        Ok(Self { map })
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        Self::from_bytes(&read_file(path)?)
+    }
+}
+
+impl<V> crate::Dictionary for Dictionary<V> {
+    type Value = V;
+
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, V)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self> {
+        Self::new_with_values(patterns_with_entries_and_values, normalization)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        Self::entry(self, index_of_patterns)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        Self::value(self, index_of_patterns)
+    }
+
+    fn total_frequency(&self) -> usize {
+        Self::total_frequency(self)
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        Self::max_pattern_chars(self)
+    }
+
+    fn normalization(&self) -> Normalization {
+        Self::normalization(self)
     }
 }
 
 fn prepare_patterns_for_dictionary<
     T: AsRef<str>,
-    I: IntoIterator<Item = T>
+    I: IntoIterator<Item = (T, PatternEntry)>
 >(
-    patterns: I,
-) -> UltraNLPResult<Vec<(String, usize)>> {
-    let patterns_with_values = patterns
+    patterns_with_entries: I,
+    normalization: Normalization,
+) -> UltraNLPResult<(Vec<(String, usize)>, PatternEntries)> {
+    let (patterns_with_values, entries): (Vec<_>, Vec<_>) = patterns_with_entries
         .into_iter()
         .enumerate()
-        .map(|(index, pattern)| -> Result<(String, usize), _>{
-            let pattern = pattern
-                .as_ref()
-                .to_lowercase();
+        .map(|(index, (pattern, entry))| {
+            // 与分词时对文本的规范化保持一致.
+            let pattern = normalization.normalize(pattern.as_ref());
 
-            let value = usize::try_from(index)
-                .map_err(|err| UltraNLPError::new(err.to_string()))?;
-
-            Ok((pattern, value))
+            ((pattern, index), entry)
         })
-        .collect::<Result<Vec<_>, _>>()?;
+        .unzip();
+    validate_patterns(
+        patterns_with_values
+            .iter()
+            .map(|(pattern, _)| pattern.as_str())
+    )?;
 
-    Ok(patterns_with_values)
+    let max_pattern_chars = patterns_with_values
+        .iter()
+        .map(|(pattern, _)| pattern.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    Ok((patterns_with_values, PatternEntries::new(entries, max_pattern_chars)))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::hashmap::Dictionary;
+    use crate::{BehaviorForUnmatched, Normalization, PatternEntry, UltraNLPError};
+    use crate::hashmap::{segment_forward_longest, Dictionary};
 
     #[test]
     fn test_empty_patterns() {
@@ -84,4 +419,175 @@ mod tests {
 
         assert!(Dictionary::new(patterns).is_err());
     }
+
+    #[test]
+    fn test_patterns_with_frequencies() {
+        let patterns_with_frequencies = vec![("foo", 10), ("bar", 20)];
+
+        let dict = Dictionary::new_with_frequencies(patterns_with_frequencies).unwrap();
+
+        assert_eq!(dict.entry(0).unwrap().frequency(), 10);
+        assert_eq!(dict.entry(1).unwrap().tag(), None);
+        assert_eq!(dict.total_frequency(), 30);
+    }
+
+    #[test]
+    fn test_patterns_with_tagged_frequencies() {
+        let patterns_with_tagged_frequencies = vec![("foo", 10, "n"), ("bar", 20, "v")];
+
+        let dict = Dictionary::new_with_tagged_frequencies(
+            patterns_with_tagged_frequencies
+        ).unwrap();
+
+        assert_eq!(dict.entry(1).unwrap().frequency(), 20);
+        assert_eq!(dict.entry(1).unwrap().tag(), Some("v"));
+        assert!(dict.entry(2).is_none());
+    }
+
+    #[test]
+    fn test_to_bytes_and_from_bytes() {
+        let text = "商品和服务";
+        let dict = Dictionary::new_with_tagged_frequencies(
+            vec![("商品", 10, "n"), ("和服", 20, "n"), ("服务", 30, "vn")]
+        ).unwrap();
+
+        let bytes = dict.to_bytes();
+        let result = <Dictionary>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(result.entry(2).unwrap().tag(), Some("vn"));
+        assert_eq!(result.total_frequency(), 60);
+        assert_eq!(result.max_pattern_chars(), 2);
+        assert_eq!(
+            segment_forward_longest(text, &result, BehaviorForUnmatched::KeepAsWords),
+            segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+        );
+    }
+
+    #[test]
+    fn test_from_corrupted_bytes() {
+        let dict = Dictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+        let mut bytes = dict.to_bytes();
+        let index = bytes.len() / 2;
+        bytes[index] ^= 1;
+
+        assert!(<Dictionary>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_with_wrong_max_pattern_chars() {
+        let mut dict = Dictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+        dict.entries.set_max_pattern_chars(1);
+
+        let result = <Dictionary>::from_bytes(&dict.to_bytes());
+
+        assert!(matches!(result, Err(UltraNLPError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(
+            format!("ultra-nlp-hashmap-dictionary-{}.bin", std::process::id())
+        );
+        let dict = Dictionary::new(vec!["foo", "bar"]).unwrap();
+
+        dict.save(&path).unwrap();
+        let result = <Dictionary>::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap().to_bytes().len(), dict.to_bytes().len());
+    }
+
+    #[test]
+    fn test_insert() {
+        let text = "商品服务";
+        let mut dict = Dictionary::new(vec!["商品", "和服"]).unwrap();
+
+        let result = dict.insert("服务", PatternEntry::new(30, Some("vn"))).unwrap();
+        let replaced = dict.insert("商品", PatternEntry::new(10, Some("n"))).unwrap();
+
+        assert!(result.is_none());
+        assert_eq!(replaced, Some(PatternEntry::default()));
+        assert!(dict.insert("", PatternEntry::default()).is_err());
+        assert_eq!(dict.entry(2).unwrap().tag(), Some("vn"));
+        assert_eq!(dict.total_frequency(), 41);
+        assert_eq!(
+            segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("商品", Some(0)), ("服务", Some(2))]
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let text = "商品和服务";
+        let mut dict = Dictionary::new_with_frequencies(
+            vec![("商品", 10), ("和服", 20), ("服务", 30)]
+        ).unwrap();
+
+        let result = dict.remove("和服").unwrap();
+
+        assert_eq!(result.entry(), &PatternEntry::new::<&str>(20, None));
+        assert_eq!(result.index_of_patterns(), 1);
+        assert_eq!(result.moved_index_of_patterns(), Some(2));
+        assert!(dict.remove("和服").is_none());
+        assert_eq!(dict.entry(1).unwrap().frequency(), 30);
+        assert_eq!(dict.total_frequency(), 40);
+        assert_eq!(
+            segment_forward_longest(text, &dict, BehaviorForUnmatched::Ignore)
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("商品", Some(0)), ("服务", Some(1))]
+        );
+
+        let bytes = dict.to_bytes();
+        assert_eq!(<Dictionary>::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_insert_with_value_and_remove() {
+        let text = "和服服务";
+        let mut dict = Dictionary::new_with_values(
+            vec![
+                ("商品", PatternEntry::default(), "product"),
+                ("和服", PatternEntry::default(), "kimono"),
+            ],
+            Normalization::default(),
+        ).unwrap();
+
+        let inserted = dict.insert_with_value("服务", PatternEntry::default(), "service").unwrap();
+        let removed = dict.remove("商品").map(|x| x.into_entry_and_value());
+
+        assert!(inserted.is_none());
+        assert_eq!(removed, Some((PatternEntry::default(), "product")));
+        assert_eq!(dict.value(0), Some(&"service"));
+        assert_eq!(
+            segment_forward_longest(text, &dict, BehaviorForUnmatched::Ignore)
+                .into_iter()
+                .map(|x| x.value(&dict).copied())
+                .collect::<Vec<_>>(),
+            vec![Some("kimono"), Some("service")]
+        );
+    }
+
+    #[test]
+    fn test_to_bytes_and_from_bytes_with_normalization() {
+        let dict = Dictionary::new_with_normalization(
+            vec![("iPhone", PatternEntry::default()), ("IPHONE", PatternEntry::default())],
+            Normalization::case_sensitive(),
+        ).unwrap();
+
+        let result = <Dictionary>::from_bytes(&dict.to_bytes()).unwrap();
+
+        assert_eq!(result.normalization(), Normalization::case_sensitive());
+        assert_eq!(
+            segment_forward_longest("IPHONE", &result, BehaviorForUnmatched::Ignore)
+                .into_iter()
+                .map(|x| x.index_of_patterns())
+                .collect::<Vec<_>>(),
+            vec![Some(1)]
+        );
+    }
 }
@@ -1,4 +1,6 @@
 use crate::utils::split_as_char_ranges;
+use crate::normalized_text::NormalizedText;
+use crate::hmm::recognize_unmatched_words;
 use crate::{
     Match,
     TextRange,
@@ -6,15 +8,17 @@ use crate::{
 };
 use crate::hashmap::Dictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_backward_longest<T: AsRef<str>>(
+/// Segments the text by backward longest matching.
+///
+/// There is no lazy version, because backward matching starts from the end of the text
+/// and cannot emit the words in the order of the text.
+pub fn segment_backward_longest<T: AsRef<str>, V>(
     text: T,
-    dict: &Dictionary,
+    dict: &Dictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text
-        .as_ref()
-        .to_lowercase();
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text.as_str();
 
     let mut results: Vec<Match> = vec![];
 
@@ -30,9 +34,8 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                 usize, // start_index
                 usize, // value
             )> = None;
-            (0..end_index)
+            (dict.min_start_index(text, end_index)..end_index)
                 .rev()
-                .into_iter()
                 .for_each(|start_index| {
                     if text.is_char_boundary(start_index) {
                         let sub_text = &text[start_index..end_index];
@@ -60,8 +63,9 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                 let mut unmatched_results: Vec<Match> = vec![];
 
                 match behavior_for_unmatched {
-                    BehaviorForUnmatched::KeepAsWords => {
-                        if matched_results.len() > 0 {
+                    | BehaviorForUnmatched::KeepAsWords
+                    | BehaviorForUnmatched::KeepAsHmmWords(_) => {
+                        if !matched_results.is_empty() {
                             // 将之前未消耗的word作为Match提交
                             if let Some(index) = unconsumed_end_index {
                                 let result = Match::new(
@@ -74,16 +78,13 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                                 unmatched_results.push(result);
                                 unconsumed_end_index = None;
                             }
-                        } else {
-                            if end_index <= minimum_matched_start_index {
-                                if let None = unconsumed_end_index {
-                                    unconsumed_end_index = Some(end_index);
-                                }
-                            }
+                        } else if end_index <= minimum_matched_start_index
+                            && unconsumed_end_index.is_none() {
+                            unconsumed_end_index = Some(end_index);
                         }
                     },
                     BehaviorForUnmatched::KeepAsChars => {
-                        if matched_results.len() > 0 {
+                        if !matched_results.is_empty() {
                             // 将之前未消耗的char作为Match提交
                             if let Some(index) = unconsumed_end_index {
                                 let iter = split_as_char_ranges(&text[end_index..index])
@@ -101,12 +102,9 @@ pub fn segment_backward_longest<T: AsRef<str>>(
                                 unmatched_results.reverse();
                                 unconsumed_end_index = None;
                             }
-                        } else {
-                            if end_index >= minimum_matched_start_index {
-                                if let None = unconsumed_end_index {
-                                    unconsumed_end_index = Some(end_index);
-                                }
-                            }
+                        } else if end_index >= minimum_matched_start_index
+                            && unconsumed_end_index.is_none() {
+                            unconsumed_end_index = Some(end_index);
                         }
                     },
                     BehaviorForUnmatched::Ignore => (),
@@ -126,7 +124,8 @@ pub fn segment_backward_longest<T: AsRef<str>>(
     if minimum_matched_start_index > 0 {
         // 处理text剩余的文本
         match behavior_for_unmatched {
-            BehaviorForUnmatched::KeepAsWords => {
+            | BehaviorForUnmatched::KeepAsWords
+            | BehaviorForUnmatched::KeepAsHmmWords(_) => {
                 results.push(Match::new(
                     TextRange::new(
                         0,
@@ -155,7 +154,10 @@ pub fn segment_backward_longest<T: AsRef<str>>(
 
     results.reverse();
 
-    results
+    recognize_unmatched_words(results, text, &behavior_for_unmatched)
+        .into_iter()
+        .map(|mat| normalized_text.to_original_match(mat))
+        .collect()
 }
 
 #[cfg(test)]
@@ -305,4 +307,26 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = Dictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_backward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
 }
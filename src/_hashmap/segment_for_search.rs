@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use crate::{
+    Match,
+    TextRange,
+    BehaviorForUnmatched,
+    normalized_text::NormalizedText,
+    segments::{MatchSource, Segments},
+};
+use crate::hashmap::Dictionary;
+use super::segment_forward_longest::ForwardLongestMatches;
+
+/// Segments the text by forward longest matching,
+/// and emits the words inside each matched word before the matched word itself.
+pub fn segment_for_search<T: AsRef<str>, V>(
+    text: T,
+    dict: &Dictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> Vec<Match> {
+    segment_for_search_iter(text, dict, behavior_for_unmatched).collect()
+}
+
+/// Segments the text for search lazily, see `segment_for_search`.
+pub fn segment_for_search_iter<'a, T: AsRef<str>, V>(
+    text: T,
+    dict: &'a Dictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> ForSearchIterator<'a, V> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let source = ForSearchMatches {
+        forward_longest_matches: ForwardLongestMatches::new(dict),
+        standard_dict: dict,
+        pending: VecDeque::new(),
+        covered_range: TextRange::new(0, 0),
+    };
+
+    ForSearchIterator {
+        segments: Segments::new(
+            source,
+            Arc::new(normalized_text),
+            behavior_for_unmatched,
+        ),
+    }
+}
+
+pub struct ForSearchIterator<'a, V> {
+    segments: Segments<ForSearchMatches<'a, V>>,
+}
+
+impl<V> Iterator for ForSearchIterator<'_, V> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+}
+
+struct ForSearchMatches<'a, V> {
+    forward_longest_matches: ForwardLongestMatches<'a, V>,
+    standard_dict: &'a Dictionary<V>,
+    // 当前匹配的词之内的词, 最后是匹配的词本身.
+    pending: VecDeque<Match>,
+    // 当前匹配的词的范围, 其中的词之前的未匹配内容在匹配的词开始处结束.
+    covered_range: TextRange,
+}
+
+impl<V> MatchSource for ForSearchMatches<'_, V> {
+    fn next_match(&mut self, text: &str) -> Option<Match> {
+        if self.pending.is_empty() {
+            let mat = self.forward_longest_matches.next_match(text)?;
+            let range = mat.range();
+
+            // 只对匹配的词建立DAG, 其中的范围相对于匹配的词.
+            let dag = self.standard_dict.build_dag(&text[range.start_index()..range.end_index()]);
+            let words = dag
+                .words_inside(TextRange::new(0, range.len()))
+                .into_iter()
+                .map(|word| {
+                    Match::new(
+                        TextRange::new(
+                            range.start_index() + word.range().start_index(),
+                            range.start_index() + word.range().end_index(),
+                        ),
+                        word.index_of_patterns(),
+                    )
+                });
+
+            self.pending.extend(words);
+            self.pending.push_back(mat);
+            self.covered_range = range;
+        }
+
+        self.pending.pop_front()
+    }
+
+    fn covered_range(&self, _mat: &Match) -> TextRange {
+        self.covered_range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BehaviorForUnmatched;
+    use crate::hashmap::{
+        segment_for_search,
+        segment_for_search_iter,
+        Dictionary,
+    };
+
+    #[test]
+    fn test_ignore_unmatched() {
+        let text = " 中华人民共和国成立了, hello world ";
+        let patterns = vec!["中华", "华人", "人民", "共和", "共和国", "中华人民共和国", "成立"];
+        let dict = Dictionary::new(patterns).unwrap();
+
+        let result = segment_for_search(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "中华",
+                "华人",
+                "人民",
+                "共和",
+                "共和国",
+                "中华人民共和国",
+                "成立",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_words() {
+        let text = " 中华人民共和国成立了, hello world ";
+        let patterns = vec!["中华", "华人", "人民", "共和", "共和国", "中华人民共和国", "成立"];
+        let dict = Dictionary::new(patterns).unwrap();
+
+        let result = segment_for_search(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                " ",
+                "中华",
+                "华人",
+                "人民",
+                "共和",
+                "共和国",
+                "中华人民共和国",
+                "成立",
+                "了, hello world ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value() {
+        let text = "中华人民共和国";
+        let patterns = vec!["中华", "华人", "人民", "共和", "共和国", "中华人民共和国", "成立"];
+        let dict = Dictionary::new(patterns).unwrap();
+
+        let result = segment_for_search(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.index_of_patterns().unwrap())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let text = "中华人民共和国成立了".repeat(100);
+        let patterns = vec!["中华", "华人", "人民", "共和", "共和国", "中华人民共和国", "成立"];
+        let dict = Dictionary::new(patterns).unwrap();
+
+        let result = segment_for_search_iter(
+            &text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .take(9)
+                .map(|x| x.range().extract(&text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "中华",
+                "华人",
+                "人民",
+                "共和",
+                "共和国",
+                "中华人民共和国",
+                "成立",
+                "了",
+                "中华",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sub_word_after_start_of_matched_word() {
+        let text = "中华人民共和国";
+        let patterns = vec!["华人", "中华人民共和国"];
+        let dict = Dictionary::new(patterns).unwrap();
+
+        for behavior_for_unmatched in [
+            BehaviorForUnmatched::KeepAsChars,
+            BehaviorForUnmatched::KeepAsWords,
+        ] {
+            let result = segment_for_search(
+                text,
+                &dict,
+                behavior_for_unmatched
+            );
+
+            assert_eq!(
+                result
+                    .into_iter()
+                    .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                    .collect::<Vec<_>>(),
+                vec![
+                    ("华人", Some(0)),
+                    ("中华人民共和国", Some(1)),
+                ]
+            );
+        }
+    }
+}
@@ -1,146 +1,112 @@
-use crate::utils::split_as_char_ranges;
+use std::sync::Arc;
 use crate::{
     Match,
     TextRange,
     BehaviorForUnmatched,
+    normalized_text::NormalizedText,
+    segments::{MatchSource, Segments},
 };
 use crate::hashmap::Dictionary;
 
-// 待generator稳定, 改为generator, 以便返回Iterator.
-pub fn segment_forward_longest<T: AsRef<str>>(
+pub fn segment_forward_longest<T: AsRef<str>, V>(
     text: T,
-    dict: &Dictionary,
+    dict: &Dictionary<V>,
     behavior_for_unmatched: BehaviorForUnmatched,
 ) -> Vec<Match> {
-    let text = text
-        .as_ref()
-        .to_lowercase();
+    segment_forward_longest_iter(text, dict, behavior_for_unmatched).collect()
+}
+
+/// Segments the text by forward longest matching lazily.
+pub fn segment_forward_longest_iter<'a, T: AsRef<str>, V>(
+    text: T,
+    dict: &'a Dictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> ForwardLongestIterator<'a, V> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let source = ForwardLongestMatches::new(dict);
 
-    let mut results: Vec<Match> = vec![];
+    ForwardLongestIterator {
+        segments: Segments::new(
+            source,
+            Arc::new(normalized_text),
+            behavior_for_unmatched,
+        ),
+    }
+}
+
+pub struct ForwardLongestIterator<'a, V> {
+    segments: Segments<ForwardLongestMatches<'a, V>>,
+}
 
-    let mut unconsumed_start_index: Option<usize> = None;
-    let mut maximum_matched_end_index = 0;
-    let mut start_index = 0;
-    while start_index < text.len() {
-        if text.is_char_boundary(start_index) {
-            let mut next_start_index = start_index + 1;
+impl<V> Iterator for ForwardLongestIterator<'_, V> {
+    type Item = Match;
 
-            let mut matched_results: Vec<Match> = vec![];
-            let mut longest_match: Option<(
+    fn next(&mut self) -> Option<Self::Item> {
+        self.segments.next()
+    }
+}
+
+pub(crate) struct ForwardLongestMatches<'a, V> {
+    dict: &'a Dictionary<V>,
+    start_index: usize,
+}
+
+impl<'a, V> ForwardLongestMatches<'a, V> {
+    pub(crate) fn new(dict: &'a Dictionary<V>) -> Self {
+        Self {
+            dict,
+            start_index: 0,
+        }
+    }
+}
+
+impl<V> MatchSource for ForwardLongestMatches<'_, V> {
+    fn next_match(&mut self, text: &str) -> Option<Match> {
+        while self.start_index < text.len() {
+            let start_index = self.start_index;
+
+            let longest_match: Option<(
                 usize, // end_index
                 usize, // value
-            )> = None;
-            ((start_index + 1)..=text.len())
-                .into_iter()
-                .for_each(|end_index| {
-                    if text.is_char_boundary(end_index) {
-                        let sub_text = &text[start_index..end_index];
-
-                        if let Some(value) = dict.map.get(sub_text) {
-                            longest_match = Some((end_index, *value))
-                        }
-                    }
+            )> = ((start_index + 1)..=self.dict.max_end_index(text, start_index))
+                .rev()
+                .filter(|end_index| text.is_char_boundary(*end_index))
+                .find_map(|end_index| {
+                    self.dict.map
+                        .get(&text[start_index..end_index])
+                        .map(|value| (end_index, *value))
                 });
 
-            if let Some((end_index, value)) = longest_match{
-                let range = TextRange::new(
-                    start_index,
-                    end_index
-                );
-
-                let result = Match::new(range, Some(value));
-                matched_results.push(result);
-
-                next_start_index = range.end_index();
-                maximum_matched_end_index = range.end_index();
-            }
+            match longest_match {
+                Some((end_index, value)) => {
+                    self.start_index = end_index;
 
-            let mut unmatched_results: Vec<Match> = vec![];
-            match behavior_for_unmatched {
-                BehaviorForUnmatched::KeepAsWords => {
-                    if matched_results.len() > 0 {
-                        // 将之前未消耗的word作为Match提交
-                        if let Some(index) = unconsumed_start_index {
-                            let result = Match::new(
-                                TextRange::new(index, start_index),
-                                None,
-                            );
-                            unmatched_results.push(result);
-                            unconsumed_start_index = None;
-                        }
-                    } else {
-                        if start_index >= maximum_matched_end_index {
-                            if let None = unconsumed_start_index {
-                                unconsumed_start_index = Some(start_index);
-                            }
-                        }
-                    }
+                    return Some(Match::new(
+                        TextRange::new(start_index, end_index),
+                        Some(value),
+                    ));
                 },
-                BehaviorForUnmatched::KeepAsChars => {
-                    if matched_results.len() > 0 {
-                        // 将之前未消耗的char作为Match提交
-                        if let Some(index) = unconsumed_start_index {
-                            let result = Match::new(
-                                TextRange::new(index, start_index),
-                                None,
-                            );
-                            unmatched_results.push(result);
-                            unconsumed_start_index = None;
-                        }
-                    } else {
-                        if start_index >= maximum_matched_end_index {
-                            if let None = unconsumed_start_index {
-                                unconsumed_start_index = Some(start_index);
-                            }
-                        }
-                    }
+                None => {
+                    self.start_index += text[start_index..]
+                        .chars()
+                        .next()
+                        .map(|char| char.len_utf8())
+                        .unwrap_or(1);
                 },
-                BehaviorForUnmatched::Ignore => (),
             }
-
-            results.append(&mut unmatched_results);
-            results.append(&mut matched_results);
-
-            start_index = next_start_index;
-        } else {
-            start_index += 1;
         }
-    }
-    if maximum_matched_end_index < text.len() {
-        // 处理text剩余的文本
-        match behavior_for_unmatched {
-            BehaviorForUnmatched::KeepAsWords => {
-                results.push(Match::new(
-                    TextRange::new(maximum_matched_end_index, text.len()),
-                    None
-                ))
-            },
-            BehaviorForUnmatched::KeepAsChars => {
-                let iter = split_as_char_ranges(&text[maximum_matched_end_index..])
-                    .map(|range| {
-                        Match::new(
-                            TextRange::new(
-                                maximum_matched_end_index + range.start_index(),
-                                maximum_matched_end_index + range.end_index(),
-                            ),
-                            None
-                        )
-                    });
-
-                results.extend(iter);
-            }
-            BehaviorForUnmatched::Ignore => (),
-        }
-    }
 
-    results
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BehaviorForUnmatched;
+    use std::sync::Arc;
+    use crate::{BehaviorForUnmatched, HmmModel};
     use crate::hashmap::{
         segment_forward_longest,
+        segment_forward_longest_iter,
         Dictionary,
     };
 
@@ -282,4 +248,119 @@ mod tests {
             vec!["你好", "世界"]
         );
     }
+
+    #[test]
+    fn test_text_length_changed_by_lowercasing() {
+        let text = "İ商品和服务ẞ";
+        let dict = Dictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["İ", "商品", "和", "服务", "ẞ"]
+        );
+    }
+
+    #[test]
+    fn test_final_sigma() {
+        let text = "ΣΊΣΥΦΟΣ";
+        let dict = Dictionary::new(
+            vec!["σίσυφος"]
+        ).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                .collect::<Vec<_>>(),
+            vec![("ΣΊΣΥΦΟΣ", Some(0))]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_hmm_words() {
+        let text = "他来到了网易杭研大厦";
+        let dict = Dictionary::new(
+            vec!["来到", "网易"]
+        ).unwrap();
+        let model = HmmModel::train(vec![
+            vec!["我", "来到", "北京", "清华大学"],
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"],
+            vec!["小明", "硕士", "毕业", "于", "中国", "科学院"],
+        ]).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsHmmWords(Arc::new(model))
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"]
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let text = "商品和服务".repeat(100);
+        let dict = Dictionary::new(
+            vec!["商品", "和服", "服务"]
+        ).unwrap();
+
+        let result = segment_forward_longest_iter(
+            &text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .take(4)
+                .map(|x| x.range().extract(&text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "和服", "务", "商品"]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_chars_between_matches() {
+        let text = "商品abc服务";
+        let dict = Dictionary::new(
+            vec!["商品", "服务"]
+        ).unwrap();
+
+        let result = segment_forward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "a", "b", "c", "服务"]
+        );
+    }
 }
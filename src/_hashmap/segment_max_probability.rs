@@ -0,0 +1,198 @@
+use crate::{
+    Match,
+    BehaviorForUnmatched,
+    normalized_text::NormalizedText,
+    hmm::recognize_unmatched_words,
+    dag::apply_behavior_for_unmatched,
+};
+use crate::hashmap::Dictionary;
+
+// 需要整个文本的DAG才能确定最优路径, 因此无法惰性地返回结果.
+pub fn segment_max_probability<T: AsRef<str>, V>(
+    text: T,
+    dict: &Dictionary<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> Vec<Match> {
+    let normalized_text = NormalizedText::new(text.as_ref(), dict.normalization());
+    let text = normalized_text.as_str();
+
+    let dag = dict.build_dag(text);
+    let path = dag.max_probability_path(&dict.entries);
+
+    let results = apply_behavior_for_unmatched(path, &behavior_for_unmatched);
+
+    recognize_unmatched_words(results, text, &behavior_for_unmatched)
+        .into_iter()
+        .map(|mat| normalized_text.to_original_match(mat))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::{BehaviorForUnmatched, HmmModel};
+    use crate::test_utils::create_dict;
+    use crate::hashmap::{
+        segment_max_probability,
+        Dictionary,
+    };
+
+    #[test]
+    fn test_ignore_unmatched() {
+        let text = " 商品和服务, hello world ";
+        let dict = create_dict();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "和", "服务"]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_chars() {
+        let text = " 商品和服务, hello world ";
+        let dict = create_dict();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                " ",
+                "商品",
+                "和",
+                "服务",
+                ",",
+                " ",
+                "h",
+                "e",
+                "l",
+                "l",
+                "o",
+                " ",
+                "w",
+                "o",
+                "r",
+                "l",
+                "d",
+                " ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_words() {
+        let text = " 商品和服务, hello world ";
+        let dict = create_dict();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsWords
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                " ",
+                "商品",
+                "和",
+                "服务",
+                ", hello world ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_value() {
+        let text = " 商品和服务, hello world ";
+        let dict = create_dict();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::Ignore
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.index_of_patterns().unwrap())
+                .collect::<Vec<_>>(),
+            vec![0, 1, 3]
+        );
+    }
+
+    #[test]
+    fn test_frequency_decides_ambiguity() {
+        let text = "商品和服务";
+        let dict = Dictionary::new_with_frequencies(
+            vec![
+                ("商品", 100),
+                ("和", 1),
+                ("和服", 100),
+                ("服务", 1),
+            ]
+        ).unwrap();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "和服", "务"]
+        );
+    }
+
+    #[test]
+    fn test_keep_unmatched_as_hmm_words() {
+        let text = "他来到了网易杭研大厦";
+        let dict = Dictionary::new(
+            vec!["来到", "网易"]
+        ).unwrap();
+        let model = HmmModel::train(vec![
+            vec!["我", "来到", "北京", "清华大学"],
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"],
+            vec!["小明", "硕士", "毕业", "于", "中国", "科学院"],
+        ]).unwrap();
+
+        let result = segment_max_probability(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsHmmWords(Arc::new(model))
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"]
+        );
+    }
+}
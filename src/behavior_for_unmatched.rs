@@ -1,6 +1,66 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::Arc;
+use crate::HmmModel;
+
+#[derive(Debug, Clone)]
 pub enum BehaviorForUnmatched {
     Ignore,
     KeepAsChars,
     KeepAsWords,
+    /// Recognizes words from the unmatched content by the HMM model.
+    ///
+    /// Two behaviors of this variant are equal only if they share the same model,
+    /// i.e. one `Arc` is cloned from the other, two copies of a model are not equal.
+    KeepAsHmmWords(Arc<HmmModel>),
+}
+
+impl PartialEq for BehaviorForUnmatched {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::KeepAsHmmWords(model), Self::KeepAsHmmWords(other_model)) => {
+                Arc::ptr_eq(model, other_model)
+            },
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for BehaviorForUnmatched {}
+
+impl Hash for BehaviorForUnmatched {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        if let Self::KeepAsHmmWords(model) = self {
+            Arc::as_ptr(model).hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use crate::{BehaviorForUnmatched, HmmModel};
+
+    #[test]
+    fn test_eq_and_hash() {
+        let model = Arc::new(HmmModel::train(vec![vec!["你好"]]).unwrap());
+        let other_model = Arc::new(model.as_ref().clone());
+
+        let behaviors = HashSet::from([
+            BehaviorForUnmatched::Ignore,
+            BehaviorForUnmatched::KeepAsChars,
+            BehaviorForUnmatched::KeepAsChars,
+            BehaviorForUnmatched::KeepAsHmmWords(model.clone()),
+            BehaviorForUnmatched::KeepAsHmmWords(model.clone()),
+            BehaviorForUnmatched::KeepAsHmmWords(other_model.clone()),
+        ]);
+
+        assert_eq!(behaviors.len(), 4);
+        assert_ne!(
+            BehaviorForUnmatched::KeepAsHmmWords(model),
+            BehaviorForUnmatched::KeepAsHmmWords(other_model)
+        );
+    }
 }
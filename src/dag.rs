@@ -0,0 +1,443 @@
+use std::cmp::Ordering;
+use crate::{
+    Match,
+    TextRange,
+    BehaviorForUnmatched,
+    pattern_entry::PatternEntries,
+};
+
+// 路径的分数, 路径的第一条边的终点和index_of_patterns, 以及路径在第一条边的终点处的排名.
+type Route<S> = (S, usize, Option<usize>, usize);
+
+/// The directed acyclic graph of all possible words in a text.
+///
+/// Nodes are the byte indexes of char boundaries, an edge from `start_index` to
+/// `end_index` means `text[start_index..end_index]` is a word.
+pub(crate) struct Dag {
+    // 以每个字节位置为起点的边: (end_index, index_of_patterns).
+    edges: Vec<Vec<(usize, Option<usize>)>>,
+}
+
+impl Dag {
+    pub fn new(text: &str) -> Self {
+        Self {
+            edges: vec![vec![]; text.len()],
+        }
+    }
+
+    pub fn add_edge(
+        &mut self,
+        start_index: usize,
+        end_index: usize,
+        index_of_patterns: usize,
+    ) {
+        self.edges[start_index].push((end_index, Some(index_of_patterns)));
+    }
+
+    /// Adds an unmatched single char edge to every char where no word starts,
+    /// so that every char boundary can reach the end of the text.
+    pub fn fill_unmatched_chars(&mut self, text: &str) {
+        text
+            .char_indices()
+            .for_each(|(start_index, char)| {
+                let end_index = start_index + char.len_utf8();
+                let edges = &mut self.edges[start_index];
+
+                if edges.is_empty() {
+                    edges.push((end_index, None));
+                }
+            });
+    }
+
+    /// Returns the matched words inside the range, except the range itself,
+    /// ordered by start index and end index.
+    pub fn words_inside(&self, range: TextRange) -> Vec<Match> {
+        (range.start_index()..range.end_index())
+            .flat_map(|start_index| {
+                let mut words = self.edges[start_index]
+                    .iter()
+                    .filter(|(end_index, index_of_patterns)| {
+                        index_of_patterns.is_some()
+                            && *end_index <= range.end_index()
+                            && TextRange::new(start_index, *end_index) != range
+                    })
+                    .map(|(end_index, index_of_patterns)| {
+                        Match::new(
+                            TextRange::new(start_index, *end_index),
+                            *index_of_patterns,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                words.sort_by_key(|mat| mat.range().end_index());
+
+                words
+            })
+            .collect()
+    }
+
+    /// Finds the path with the maximum product of word probabilities.
+    pub fn max_probability_path(&self, entries: &PatternEntries) -> Vec<Match> {
+        let log_total_frequency = (entries.total_frequency().max(1) as f64).ln();
+
+        self.best_path(
+            0.0,
+            |log_probability: f64, index_of_patterns| {
+                let frequency = index_of_patterns
+                    .and_then(|index| entries.get(index))
+                    .map(|entry| entry.frequency())
+                    .unwrap_or(1)
+                    .max(1);
+
+                log_probability + (frequency as f64).ln() - log_total_frequency
+            },
+            |a, b| a.total_cmp(b),
+        )
+    }
+
+    /// Finds the path with the fewest words,
+    /// the path with fewer unmatched chars wins when the numbers of words are the same.
+    pub fn shortest_path(&self) -> Vec<Match> {
+        self.best_path(
+            (0, 0),
+            |(words, unmatched_chars): (usize, usize), index_of_patterns| {
+                if index_of_patterns.is_some() {
+                    (words + 1, unmatched_chars)
+                } else {
+                    (words + 1, unmatched_chars + 1)
+                }
+            },
+            // 越少越好
+            |a, b| b.cmp(a),
+        )
+    }
+
+    /// Finds the top `n` paths with the maximum products of word probabilities,
+    /// the score of each path is the natural logarithm of its probability.
+    pub fn n_best_max_probability_paths(
+        &self,
+        entries: &PatternEntries,
+        n: usize,
+    ) -> Vec<(Vec<Match>, f64)> {
+        let log_total_frequency = (entries.total_frequency().max(1) as f64).ln();
+
+        self.n_best_paths(
+            n,
+            0.0,
+            |log_probability: f64, index_of_patterns| {
+                let frequency = index_of_patterns
+                    .and_then(|index| entries.get(index))
+                    .map(|entry| entry.frequency())
+                    .unwrap_or(1)
+                    .max(1);
+
+                log_probability + (frequency as f64).ln() - log_total_frequency
+            },
+            |a, b| a.total_cmp(b),
+        )
+    }
+
+    /// Finds the best path by dynamic programming from the end of the text,
+    /// the longer word wins when the scores are the same.
+    fn best_path<S: Copy>(
+        &self,
+        initial_score: S,
+        extend: impl Fn(S, Option<usize>) -> S,
+        compare: impl Fn(&S, &S) -> Ordering,
+    ) -> Vec<Match> {
+        self.n_best_paths(1, initial_score, extend, compare)
+            .into_iter()
+            .next()
+            .map(|(path, _)| path)
+            .unwrap_or_default()
+    }
+
+    /// Finds the top `n` paths by dynamic programming from the end of the text,
+    /// the longer word wins when the scores are the same.
+    fn n_best_paths<S: Copy>(
+        &self,
+        n: usize,
+        initial_score: S,
+        extend: impl Fn(S, Option<usize>) -> S,
+        compare: impl Fn(&S, &S) -> Ordering,
+    ) -> Vec<(Vec<Match>, S)> {
+        if n == 0 {
+            return vec![];
+        }
+
+        let text_len = self.edges.len();
+        // routes[i]: 从i到文本末尾的前n条路径.
+        let mut routes: Vec<Vec<Route<S>>> = vec![vec![]; text_len + 1];
+        routes[text_len] = vec![(initial_score, text_len, None, 0)];
+        (0..text_len)
+            .rev()
+            .for_each(|start_index| {
+                let mut candidates = self.edges[start_index]
+                    .iter()
+                    .flat_map(|(end_index, index_of_patterns)| {
+                        routes[*end_index]
+                            .iter()
+                            .enumerate()
+                            .map(|(rank, (score, _, _, _))| {
+                                (
+                                    extend(*score, *index_of_patterns),
+                                    *end_index,
+                                    *index_of_patterns,
+                                    rank,
+                                )
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                candidates.sort_by(|a, b| {
+                    compare(&b.0, &a.0)
+                        .then(b.1.cmp(&a.1))
+                        .then(a.3.cmp(&b.3))
+                });
+                candidates.truncate(n);
+
+                routes[start_index] = candidates;
+            });
+
+        (0..routes[0].len())
+            .map(|rank| {
+                let (score, _, _, _) = routes[0][rank];
+
+                let mut path: Vec<Match> = vec![];
+                let mut start_index = 0;
+                let mut rank = rank;
+                while start_index < text_len {
+                    let (_, end_index, index_of_patterns, next_rank) = routes[start_index][rank];
+
+                    path.push(Match::new(
+                        TextRange::new(start_index, end_index),
+                        index_of_patterns,
+                    ));
+
+                    start_index = end_index;
+                    rank = next_rank;
+                }
+
+                (path, score)
+            })
+            .collect()
+    }
+}
+
+/// Applies the behavior for unmatched to a path that consists of
+/// matched words and unmatched single chars.
+pub(crate) fn apply_behavior_for_unmatched(
+    path: Vec<Match>,
+    behavior_for_unmatched: &BehaviorForUnmatched,
+) -> Vec<Match> {
+    match behavior_for_unmatched {
+        BehaviorForUnmatched::Ignore => {
+            path
+                .into_iter()
+                .filter(|mat| mat.index_of_patterns().is_some())
+                .collect()
+        },
+        BehaviorForUnmatched::KeepAsChars => path,
+        | BehaviorForUnmatched::KeepAsWords
+        | BehaviorForUnmatched::KeepAsHmmWords(_) => {
+            let mut results: Vec<Match> = vec![];
+
+            path
+                .into_iter()
+                .for_each(|mat| {
+                    if mat.index_of_patterns().is_none()
+                        && let Some(last) = results.last_mut()
+                        && last.index_of_patterns().is_none()
+                        && last.range().end_index() == mat.range().start_index() {
+                        // 合并连续的未匹配字符
+                        *last = Match::new(
+                            TextRange::new(
+                                last.range().start_index(),
+                                mat.range().end_index(),
+                            ),
+                            None,
+                        );
+
+                        return;
+                    }
+
+                    results.push(mat);
+                });
+
+            results
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BehaviorForUnmatched,
+        Match,
+        PatternEntry,
+        TextRange,
+        pattern_entry::PatternEntries,
+    };
+    use crate::dag::{Dag, apply_behavior_for_unmatched};
+
+    #[test]
+    fn test_max_probability_path() {
+        let text = "abc";
+        let entries = PatternEntries::new(vec![
+            PatternEntry::new::<&str>(10, None),
+            PatternEntry::new::<&str>(1, None),
+        ], 2);
+        let mut dag = Dag::new(text);
+        dag.add_edge(0, 2, 0);
+        dag.add_edge(1, 3, 1);
+        dag.fill_unmatched_chars(text);
+
+        let result = dag.max_probability_path(&entries);
+
+        assert_eq!(
+            result,
+            vec![
+                Match::new(TextRange::new(0, 2), Some(0)),
+                Match::new(TextRange::new(2, 3), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_n_best_max_probability_paths() {
+        let text = "abc";
+        let entries = PatternEntries::new(vec![
+            PatternEntry::new::<&str>(10, None),
+            PatternEntry::new::<&str>(5, None),
+            PatternEntry::new::<&str>(1, None),
+        ], 2);
+        let mut dag = Dag::new(text);
+        dag.add_edge(0, 2, 0);
+        dag.add_edge(1, 3, 1);
+        dag.add_edge(0, 1, 2);
+        dag.fill_unmatched_chars(text);
+
+        let result = dag.n_best_max_probability_paths(&entries, 3);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|(path, _)| path.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![
+                    Match::new(TextRange::new(0, 2), Some(0)),
+                    Match::new(TextRange::new(2, 3), None),
+                ],
+                vec![
+                    Match::new(TextRange::new(0, 1), Some(2)),
+                    Match::new(TextRange::new(1, 3), Some(1)),
+                ],
+            ]
+        );
+        assert!(result[0].1 > result[1].1);
+    }
+
+    #[test]
+    fn test_fill_unmatched_chars_where_no_word_starts() {
+        let text = "abc";
+        let entries = PatternEntries::new(vec![PatternEntry::new::<&str>(10, None)], 1);
+        let mut dag = Dag::new(text);
+        dag.add_edge(0, 2, 0);
+        dag.fill_unmatched_chars(text);
+
+        let result = dag.n_best_max_probability_paths(&entries, 10);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>(),
+            vec![
+                vec![
+                    Match::new(TextRange::new(0, 2), Some(0)),
+                    Match::new(TextRange::new(2, 3), None),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_n_best_zero_paths() {
+        let entries = PatternEntries::new(vec![PatternEntry::new::<&str>(10, None)], 1);
+        let empty_dag = Dag::new("");
+        let mut dag = Dag::new("a");
+        dag.add_edge(0, 1, 0);
+
+        assert!(empty_dag.n_best_max_probability_paths(&entries, 0).is_empty());
+        assert!(dag.n_best_max_probability_paths(&entries, 0).is_empty());
+        assert_eq!(empty_dag.n_best_max_probability_paths(&entries, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_words_inside() {
+        let text = "abcd";
+        let mut dag = Dag::new(text);
+        dag.add_edge(0, 3, 0);
+        dag.add_edge(0, 2, 1);
+        dag.add_edge(1, 3, 2);
+        dag.add_edge(2, 4, 3);
+        dag.fill_unmatched_chars(text);
+
+        let result = dag.words_inside(TextRange::new(0, 3));
+
+        assert_eq!(
+            result,
+            vec![
+                Match::new(TextRange::new(0, 2), Some(1)),
+                Match::new(TextRange::new(1, 3), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let text = "abcd";
+        let mut dag = Dag::new(text);
+        dag.add_edge(0, 1, 0);
+        dag.add_edge(0, 2, 1);
+        dag.add_edge(1, 4, 2);
+        dag.add_edge(2, 3, 3);
+        dag.fill_unmatched_chars(text);
+
+        let result = dag.shortest_path();
+
+        assert_eq!(
+            result,
+            vec![
+                Match::new(TextRange::new(0, 1), Some(0)),
+                Match::new(TextRange::new(1, 4), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_behavior_for_unmatched() {
+        let path = vec![
+            Match::new(TextRange::new(0, 1), None),
+            Match::new(TextRange::new(1, 2), None),
+            Match::new(TextRange::new(2, 4), Some(0)),
+            Match::new(TextRange::new(4, 5), None),
+        ];
+
+        assert_eq!(
+            apply_behavior_for_unmatched(path.clone(), &BehaviorForUnmatched::Ignore),
+            vec![Match::new(TextRange::new(2, 4), Some(0))]
+        );
+        assert_eq!(
+            apply_behavior_for_unmatched(path.clone(), &BehaviorForUnmatched::KeepAsChars),
+            path
+        );
+        assert_eq!(
+            apply_behavior_for_unmatched(path, &BehaviorForUnmatched::KeepAsWords),
+            vec![
+                Match::new(TextRange::new(0, 2), None),
+                Match::new(TextRange::new(2, 4), Some(0)),
+                Match::new(TextRange::new(4, 5), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,721 @@
+use std::collections::HashMap;
+use std::path::Path;
+use crate::{
+    Normalization,
+    PatternEntry,
+    UltraNLPError,
+    UltraNLPResult,
+    dictionary_file::{load_hanlp_dictionary, load_jieba_dictionary},
+    pattern_entry::{
+        with_default_entries,
+        with_frequency_entries,
+        with_tagged_frequency_entries,
+    },
+};
+
+/// The common interface of the dictionaries of all backends.
+///
+/// The constructors without values are available if `Value` implements `Default`,
+/// every pattern gets the default value.
+pub trait Dictionary: Sized {
+    /// The type of the values associated with the patterns, `()` if there is none.
+    type Value;
+
+    fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(
+        patterns: I
+    ) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        Self::new_with_entries(with_default_entries(patterns))
+    }
+
+    fn new_with_frequencies<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        Self::new_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    fn new_with_tagged_frequencies<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        Self::new_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    fn new_with_entries<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I
+    ) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        Self::new_with_normalization(patterns_with_entries, Normalization::default())
+    }
+
+    fn new_with_normalization<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        patterns_with_entries: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        Self::new_with_values(
+            patterns_with_entries
+                .into_iter()
+                .map(|(pattern, entry)| (pattern, entry, Self::Value::default())),
+            normalization,
+        )
+    }
+
+    /// Creates a dictionary which associates a value with each pattern,
+    /// the value of a match can be borrowed by `Match::value`.
+    fn new_with_values<
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, Self::Value)>
+    >(
+        patterns_with_entries_and_values: I,
+        normalization: Normalization,
+    ) -> UltraNLPResult<Self>;
+
+    /// Loads a jieba style dictionary file, see `parse_jieba_dictionary`.
+    ///
+    /// Duplicate patterns are reported by `UltraNLPError::DuplicateLines`.
+    fn load_jieba<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        load_jieba_dictionary(path, Self::new_with_entries)
+    }
+
+    /// Loads a HanLP style dictionary file, see `parse_hanlp_dictionary`.
+    ///
+    /// Duplicate patterns are reported by `UltraNLPError::DuplicateLines`.
+    fn load_hanlp<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self>
+    where
+        Self::Value: Default,
+    {
+        load_hanlp_dictionary(path, Self::new_with_entries)
+    }
+
+    fn entry(&self, index_of_patterns: usize) -> Option<&PatternEntry>;
+
+    fn value(&self, index_of_patterns: usize) -> Option<&Self::Value>;
+
+    fn total_frequency(&self) -> usize;
+
+    fn max_pattern_chars(&self) -> usize;
+
+    fn normalization(&self) -> Normalization;
+}
+
+/// How `DictionaryBuilder` handles patterns which are the same after normalization.
+///
+/// Except `Fail`, the duplicate patterns are removed and the following patterns are renumbered,
+/// so the indexes of patterns differ from the positions of the given patterns.
+/// Use `DictionaryBuilder::build_with_entries_and_indexes` to get the new indexes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Fails with `UltraNLPError::DuplicatePattern`.
+    #[default]
+    Fail,
+    KeepFirst,
+    /// Keeps the entry and the value of the last pattern at the index of the first pattern.
+    KeepLast,
+    /// Sums the frequencies, the tag is the tag of the entry with the highest frequency.
+    /// The value of the first pattern is kept.
+    Merge,
+}
+
+/// Patterns which are the same after normalization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicatePatterns {
+    pattern: String,
+    indexes: Vec<usize>,
+}
+
+impl DuplicatePatterns {
+    /// Returns the normalized pattern.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the indexes of the patterns in ascending order.
+    pub fn indexes(&self) -> &[usize] {
+        &self.indexes
+    }
+}
+
+/// Finds all groups of patterns which are the same after normalization,
+/// the groups are in the order of their first patterns.
+///
+/// The indexes are the positions in `patterns`, starting from 0.
+/// For a dictionary file, parse it by `parse_jieba_dictionary_with_line_numbers`
+/// or `parse_hanlp_dictionary_with_line_numbers` to map the indexes to line numbers.
+pub fn find_duplicate_patterns<T: AsRef<str>, I: IntoIterator<Item = T>>(
+    patterns: I,
+    normalization: Normalization,
+) -> Vec<DuplicatePatterns> {
+    let mut groups: Vec<DuplicatePatterns> = vec![];
+    let mut indexes_of_groups: HashMap<String, usize> = HashMap::new();
+
+    patterns
+        .into_iter()
+        .enumerate()
+        .for_each(|(index, pattern)| {
+            let pattern = normalization.normalize(pattern.as_ref());
+
+            match indexes_of_groups.get(&pattern) {
+                Some(index_of_group) => groups[*index_of_group].indexes.push(index),
+                None => {
+                    indexes_of_groups.insert(pattern.clone(), groups.len());
+                    groups.push(DuplicatePatterns { pattern, indexes: vec![index] });
+                },
+            }
+        });
+
+    groups
+        .into_iter()
+        .filter(|group| group.indexes.len() > 1)
+        .collect()
+}
+
+/// Builds dictionaries of any backend with the specified options.
+#[derive(Debug, Clone, Default)]
+pub struct DictionaryBuilder {
+    normalization: Normalization,
+    duplicate_policy: DuplicatePolicy,
+}
+
+impl DictionaryBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    pub fn build<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>
+    >(
+        &self,
+        patterns: I
+    ) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        self.build_with_entries(with_default_entries(patterns))
+    }
+
+    pub fn build_with_frequencies<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, usize)>
+    >(
+        &self,
+        patterns_with_frequencies: I
+    ) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        self.build_with_entries(with_frequency_entries(patterns_with_frequencies))
+    }
+
+    pub fn build_with_tagged_frequencies<
+        D: Dictionary,
+        T: AsRef<str>,
+        U: AsRef<str>,
+        I: IntoIterator<Item = (T, usize, U)>
+    >(
+        &self,
+        patterns_with_tagged_frequencies: I
+    ) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        self.build_with_entries(
+            with_tagged_frequency_entries(patterns_with_tagged_frequencies)
+        )
+    }
+
+    pub fn build_with_entries<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        &self,
+        patterns_with_entries: I
+    ) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        self.build_with_values(with_default_values(patterns_with_entries))
+    }
+
+    /// Builds a dictionary which associates a value with each pattern.
+    ///
+    /// Duplicate patterns keep the value of the first pattern,
+    /// except that `DuplicatePolicy::KeepLast` keeps the value of the last pattern.
+    pub fn build_with_values<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, D::Value)>
+    >(
+        &self,
+        patterns_with_entries_and_values: I
+    ) -> UltraNLPResult<D> {
+        match self.duplicate_policy {
+            DuplicatePolicy::Fail => {
+                D::new_with_values(patterns_with_entries_and_values, self.normalization)
+            },
+            duplicate_policy => {
+                let (patterns_with_entries_and_values, _) = deduplicate_patterns(
+                    patterns_with_entries_and_values,
+                    self.normalization,
+                    duplicate_policy,
+                );
+
+                D::new_with_values(patterns_with_entries_and_values, self.normalization)
+            },
+        }
+    }
+
+    /// Builds a dictionary like `build_with_entries`,
+    /// and returns the index of patterns in the dictionary for each given pattern,
+    /// which differs from its position if duplicate patterns are removed.
+    ///
+    /// A removed pattern has the index of the pattern it is merged into.
+    pub fn build_with_entries_and_indexes<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry)>
+    >(
+        &self,
+        patterns_with_entries: I
+    ) -> UltraNLPResult<(D, Vec<usize>)>
+    where
+        D::Value: Default,
+    {
+        self.build_with_values_and_indexes(with_default_values(patterns_with_entries))
+    }
+
+    /// Builds a dictionary like `build_with_values`,
+    /// and returns the indexes of patterns like `build_with_entries_and_indexes`.
+    pub fn build_with_values_and_indexes<
+        D: Dictionary,
+        T: AsRef<str>,
+        I: IntoIterator<Item = (T, PatternEntry, D::Value)>
+    >(
+        &self,
+        patterns_with_entries_and_values: I
+    ) -> UltraNLPResult<(D, Vec<usize>)> {
+        match self.duplicate_policy {
+            DuplicatePolicy::Fail => {
+                let patterns_with_entries_and_values = patterns_with_entries_and_values
+                    .into_iter()
+                    .collect::<Vec<_>>();
+                let indexes = (0..patterns_with_entries_and_values.len()).collect();
+
+                D::new_with_values(patterns_with_entries_and_values, self.normalization)
+                    .map(|dict| (dict, indexes))
+            },
+            duplicate_policy => {
+                let (patterns_with_entries_and_values, indexes) = deduplicate_patterns(
+                    patterns_with_entries_and_values,
+                    self.normalization,
+                    duplicate_policy,
+                );
+
+                D::new_with_values(patterns_with_entries_and_values, self.normalization)
+                    .map(|dict| (dict, indexes))
+            },
+        }
+    }
+
+    /// Loads a jieba style dictionary file, see `parse_jieba_dictionary`.
+    ///
+    /// Duplicate patterns are reported by `UltraNLPError::DuplicateLines`.
+    pub fn load_jieba<D: Dictionary, P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        load_jieba_dictionary(path, |patterns_with_entries| {
+            self.build_with_entries(patterns_with_entries)
+        })
+    }
+
+    /// Loads a HanLP style dictionary file, see `parse_hanlp_dictionary`.
+    ///
+    /// Duplicate patterns are reported by `UltraNLPError::DuplicateLines`.
+    pub fn load_hanlp<D: Dictionary, P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<D>
+    where
+        D::Value: Default,
+    {
+        load_hanlp_dictionary(path, |patterns_with_entries| {
+            self.build_with_entries(patterns_with_entries)
+        })
+    }
+}
+
+fn with_default_values<V: Default, T: AsRef<str>, I: IntoIterator<Item = (T, PatternEntry)>>(
+    patterns_with_entries: I,
+) -> impl Iterator<Item = (T, PatternEntry, V)> {
+    patterns_with_entries
+        .into_iter()
+        .map(|(pattern, entry)| (pattern, entry, V::default()))
+}
+
+// 保留每组重复模式中的第一个模式, 因此结果的顺序为各组第一个模式的顺序.
+// 同时返回每个模式在结果中的序号.
+fn deduplicate_patterns<
+    T: AsRef<str>,
+    V,
+    I: IntoIterator<Item = (T, PatternEntry, V)>
+>(
+    patterns_with_entries_and_values: I,
+    normalization: Normalization,
+    duplicate_policy: DuplicatePolicy,
+) -> (Vec<(T, PatternEntry, V)>, Vec<usize>) {
+    let mut results: Vec<(T, PatternEntry, V)> = vec![];
+    let mut indexes_of_results: Vec<usize> = vec![];
+    let mut indexes: HashMap<String, usize> = HashMap::new();
+
+    patterns_with_entries_and_values
+        .into_iter()
+        .for_each(|(pattern, entry, value)| {
+            let normalized_pattern = normalization.normalize(pattern.as_ref());
+
+            match indexes.get(&normalized_pattern) {
+                Some(index) => {
+                    let (_, existing_entry, existing_value) = &mut results[*index];
+
+                    match duplicate_policy {
+                        DuplicatePolicy::Fail | DuplicatePolicy::KeepFirst => {},
+                        DuplicatePolicy::KeepLast => {
+                            *existing_entry = entry;
+                            *existing_value = value;
+                        },
+                        DuplicatePolicy::Merge => {
+                            *existing_entry = merge_entries(existing_entry, &entry);
+                        },
+                    }
+
+                    indexes_of_results.push(*index);
+                },
+                None => {
+                    indexes.insert(normalized_pattern, results.len());
+                    indexes_of_results.push(results.len());
+                    results.push((pattern, entry, value));
+                },
+            }
+        });
+
+    (results, indexes_of_results)
+}
+
+fn merge_entries(first_entry: &PatternEntry, second_entry: &PatternEntry) -> PatternEntry {
+    // 频率相同时取靠前的词性.
+    let tag = if second_entry.frequency() > first_entry.frequency() {
+        second_entry.tag()
+    } else {
+        first_entry.tag()
+    };
+
+    PatternEntry::new(first_entry.frequency().saturating_add(second_entry.frequency()), tag)
+}
+
+/// Checks the normalized patterns before building a dictionary.
+pub(crate) fn validate_patterns<'a, I: IntoIterator<Item = &'a str>>(
+    patterns: I
+) -> UltraNLPResult<()> {
+    let mut indexes: HashMap<&str, usize> = HashMap::new();
+    patterns
+        .into_iter()
+        .enumerate()
+        .try_for_each(|(index, pattern)| {
+            if pattern.is_empty() {
+                return Err(UltraNLPError::EmptyPattern);
+            }
+
+            match indexes.insert(pattern, index) {
+                Some(first_index) => Err(UltraNLPError::DuplicatePattern {
+                    pattern: pattern.to_string(),
+                    first_index,
+                    second_index: index,
+                }),
+                None => Ok(()),
+            }
+        })?;
+
+    if indexes.is_empty() {
+        Err(UltraNLPError::EmptyPatterns)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BehaviorForUnmatched,
+        CaseNormalization,
+        DictionaryBuilder,
+        DuplicatePolicy,
+        Normalization,
+        PatternEntry,
+        UltraNLPError,
+        find_duplicate_patterns,
+    };
+    use crate::{cedarwood, daachorse, hashmap};
+
+    #[test]
+    fn test_case_sensitive() {
+        let text = "iPhone IPHONE iphone";
+        let builder = DictionaryBuilder::new()
+            .normalization(Normalization::case_sensitive());
+        let daachorse_dict: daachorse::ForwardDictionary = builder
+            .build(vec!["iPhone", "IPHONE"])
+            .unwrap();
+        let cedarwood_dict: cedarwood::ForwardDictionary = builder
+            .build(vec!["iPhone", "IPHONE"])
+            .unwrap();
+        let hashmap_dict: hashmap::Dictionary = builder
+            .build(vec!["iPhone", "IPHONE"])
+            .unwrap();
+
+        let results = vec![
+            daachorse::segment_forward_longest(text, &daachorse_dict, BehaviorForUnmatched::Ignore),
+            cedarwood::segment_forward_longest(text, &cedarwood_dict, BehaviorForUnmatched::Ignore),
+            hashmap::segment_forward_longest(text, &hashmap_dict, BehaviorForUnmatched::Ignore),
+        ];
+
+        results
+            .into_iter()
+            .for_each(|result| {
+                assert_eq!(
+                    result
+                        .into_iter()
+                        .map(|x| (x.range().extract(text).unwrap(), x.index_of_patterns()))
+                        .collect::<Vec<_>>(),
+                    vec![("iPhone", Some(0)), ("IPHONE", Some(1))]
+                );
+            });
+    }
+
+    #[test]
+    fn test_default_normalization_rejects_patterns_differing_in_case() {
+        let result = DictionaryBuilder::new()
+            .build::<hashmap::Dictionary, _, _>(vec!["iPhone", "IPHONE"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_full_width_and_case_fold() {
+        let text = "ＳＴＲＡＳＳＥ和ＡＢＣ１２３";
+        let normalization = Normalization::new()
+            .with_case(CaseNormalization::Fold)
+            .with_full_width_to_half_width(true);
+        let dict: daachorse::StandardDictionary = DictionaryBuilder::new()
+            .normalization(normalization)
+            .build_with_frequencies(vec![("Straße", 10), ("abc123", 20)])
+            .unwrap();
+
+        let result = daachorse::segment_fully(text, &dict, BehaviorForUnmatched::Ignore);
+
+        assert_eq!(dict.normalization(), normalization);
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["ＳＴＲＡＳＳＥ", "ＡＢＣ１２３"]
+        );
+    }
+
+    #[test]
+    fn test_canonical_normalization() {
+        let text = "ＡＢＣ１２３和abc123。「商品」";
+        let dict: cedarwood::BackwardDictionary = DictionaryBuilder::new()
+            .normalization(Normalization::canonical())
+            .build(vec!["ABC123", "商品", "\"商品\""])
+            .unwrap();
+
+        let result = cedarwood::segment_backward_longest(
+            text,
+            &dict,
+            BehaviorForUnmatched::KeepAsChars,
+        );
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["ＡＢＣ１２３", "和", "abc123", "。", "「商品」"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_pattern() {
+        let patterns = vec!["foo", "bar", "FOO"];
+
+        let results = vec![
+            DictionaryBuilder::new().build::<daachorse::StandardDictionary, _, _>(patterns.clone()).err(),
+            DictionaryBuilder::new().build::<cedarwood::BackwardDictionary, _, _>(patterns.clone()).err(),
+            DictionaryBuilder::new().build::<hashmap::Dictionary, _, _>(patterns.clone()).err(),
+        ];
+
+        results
+            .into_iter()
+            .for_each(|result| {
+                assert!(matches!(
+                    result,
+                    Some(UltraNLPError::DuplicatePattern { pattern, first_index: 0, second_index: 2 })
+                        if pattern == "foo"
+                ));
+            });
+    }
+
+    #[test]
+    fn test_empty_patterns() {
+        let patterns: Vec<&str> = vec![];
+
+        let result = DictionaryBuilder::new().build::<daachorse::ForwardDictionary, _, _>(patterns);
+
+        assert!(matches!(result, Err(UltraNLPError::EmptyPatterns)));
+    }
+
+    #[test]
+    fn test_empty_pattern() {
+        let result = DictionaryBuilder::new().build::<hashmap::Dictionary, _, _>(vec!["foo", ""]);
+
+        assert!(matches!(result, Err(UltraNLPError::EmptyPattern)));
+    }
+
+    #[test]
+    fn test_find_duplicate_patterns() {
+        let patterns = vec!["foo", "bar", "FOO", "baz", "BAR", "Foo"];
+
+        let result = find_duplicate_patterns(patterns, Normalization::default());
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| (x.pattern(), x.indexes()))
+                .collect::<Vec<_>>(),
+            vec![("foo", &[0, 2, 5][..]), ("bar", &[1, 4][..])]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let patterns_with_tagged_frequencies = vec![
+            ("foo", 10, "n"),
+            ("bar", 20, "v"),
+            ("FOO", 30, "v"),
+        ];
+        let build = |duplicate_policy| {
+            DictionaryBuilder::new()
+                .duplicate_policy(duplicate_policy)
+                .build_with_tagged_frequencies::<hashmap::Dictionary, _, _, _>(
+                    patterns_with_tagged_frequencies.clone()
+                )
+                .unwrap()
+        };
+
+        let keep_first = build(DuplicatePolicy::KeepFirst);
+        let keep_last = build(DuplicatePolicy::KeepLast);
+        let merge = build(DuplicatePolicy::Merge);
+
+        assert_eq!(keep_first.entry(0), Some(&PatternEntry::new(10, Some("n"))));
+        assert!(keep_first.entry(2).is_none());
+        assert_eq!(keep_last.entry(0), Some(&PatternEntry::new(30, Some("v"))));
+        assert_eq!(merge.entry(0), Some(&PatternEntry::new(40, Some("v"))));
+        assert_eq!(merge.entry(1), Some(&PatternEntry::new(20, Some("v"))));
+        assert_eq!(merge.total_frequency(), 60);
+    }
+
+    #[test]
+    fn test_merge_large_frequencies() {
+        let result = DictionaryBuilder::new()
+            .duplicate_policy(DuplicatePolicy::Merge)
+            .build_with_frequencies::<hashmap::Dictionary, _, _>(
+                vec![("foo", usize::MAX), ("FOO", 10)]
+            )
+            .unwrap();
+
+        assert_eq!(result.entry(0).unwrap().frequency(), usize::MAX);
+    }
+
+    #[test]
+    fn test_build_with_entries_and_indexes() {
+        let patterns_with_entries = vec!["foo", "bar", "FOO", "baz"]
+            .into_iter()
+            .map(|pattern| (pattern, PatternEntry::default()))
+            .collect::<Vec<_>>();
+
+        let (dict, indexes) = DictionaryBuilder::new()
+            .duplicate_policy(DuplicatePolicy::KeepFirst)
+            .build_with_entries_and_indexes::<daachorse::ForwardDictionary, _, _>(patterns_with_entries)
+            .unwrap();
+        let result = daachorse::segment_forward_longest("baz", &dict, BehaviorForUnmatched::Ignore);
+
+        assert_eq!(indexes, vec![0, 1, 0, 2]);
+        assert_eq!(result[0].index_of_patterns(), Some(indexes[3]));
+    }
+
+    #[test]
+    fn test_build_with_values() {
+        let patterns_with_entries_and_values = vec![
+            ("foo", PatternEntry::default(), 1),
+            ("bar", PatternEntry::default(), 2),
+            ("FOO", PatternEntry::default(), 3),
+        ];
+        let build = |duplicate_policy| {
+            DictionaryBuilder::new()
+                .duplicate_policy(duplicate_policy)
+                .build_with_values::<cedarwood::ForwardDictionary<i32>, _, _>(
+                    patterns_with_entries_and_values.clone()
+                )
+                .unwrap()
+        };
+
+        let keep_first = build(DuplicatePolicy::KeepFirst);
+        let keep_last = build(DuplicatePolicy::KeepLast);
+        let merge = build(DuplicatePolicy::Merge);
+
+        assert_eq!(keep_first.value(0), Some(&1));
+        assert_eq!(keep_last.value(0), Some(&3));
+        assert_eq!(merge.value(0), Some(&1));
+        assert_eq!(merge.value(1), Some(&2));
+    }
+}
@@ -0,0 +1,339 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use crate::{
+    PatternEntry,
+    UltraNLPError,
+    UltraNLPResult,
+};
+
+const JIEBA_DICTIONARY: &str = "jieba dictionary";
+
+const HANLP_DICTIONARY: &str = "HanLP dictionary";
+
+/// Parses a jieba style dictionary, each line is `word [frequency] [tag]`
+/// separated by whitespaces.
+///
+/// The frequency defaults to 1 when it is omitted.
+pub fn parse_jieba_dictionary<R: BufRead>(
+    reader: R
+) -> UltraNLPResult<Vec<(String, PatternEntry)>> {
+    parse_jieba_dictionary_with_line_numbers(reader).map(without_line_numbers)
+}
+
+/// Parses a jieba style dictionary like `parse_jieba_dictionary`,
+/// and keeps the line number of each pattern, which starts from 1.
+pub fn parse_jieba_dictionary_with_line_numbers<R: BufRead>(
+    reader: R
+) -> UltraNLPResult<Vec<(usize, String, PatternEntry)>> {
+    parse_lines(reader, |line, fields| {
+        let invalid_line = || {
+            UltraNLPError::InvalidLine { format: JIEBA_DICTIONARY, line }
+        };
+
+        match fields {
+            [word] => Ok((word.to_string(), PatternEntry::default())),
+            [word, frequency] => {
+                let frequency = parse_frequency(frequency).ok_or_else(invalid_line)?;
+
+                Ok((word.to_string(), PatternEntry::new::<&str>(frequency, None)))
+            },
+            [word, frequency, tag] => {
+                let frequency = parse_frequency(frequency).ok_or_else(invalid_line)?;
+
+                Ok((word.to_string(), PatternEntry::new(frequency, Some(tag))))
+            },
+            _ => Err(invalid_line()),
+        }
+    })
+}
+
+/// Parses a HanLP style dictionary, each line is `word [tag frequency]...`
+/// separated by whitespaces.
+///
+/// The frequency of a word is the sum of the frequencies of its tags,
+/// and the tag of a word is the tag with the highest frequency.
+pub fn parse_hanlp_dictionary<R: BufRead>(
+    reader: R
+) -> UltraNLPResult<Vec<(String, PatternEntry)>> {
+    parse_hanlp_dictionary_with_line_numbers(reader).map(without_line_numbers)
+}
+
+/// Parses a HanLP style dictionary like `parse_hanlp_dictionary`,
+/// and keeps the line number of each pattern, which starts from 1.
+pub fn parse_hanlp_dictionary_with_line_numbers<R: BufRead>(
+    reader: R
+) -> UltraNLPResult<Vec<(usize, String, PatternEntry)>> {
+    parse_lines(reader, |line, fields| {
+        let invalid_line = || {
+            UltraNLPError::InvalidLine { format: HANLP_DICTIONARY, line }
+        };
+
+        let (word, tags_with_frequencies) = fields
+            .split_first()
+            .ok_or_else(invalid_line)?;
+        if tags_with_frequencies.is_empty() {
+            return Ok((word.to_string(), PatternEntry::default()));
+        }
+        if tags_with_frequencies.len() % 2 != 0 {
+            return Err(invalid_line());
+        }
+
+        let tags_with_frequencies = tags_with_frequencies
+            .chunks(2)
+            .map(|pair| {
+                let frequency = parse_frequency(pair[1]).ok_or_else(invalid_line)?;
+
+                Ok((pair[0], frequency))
+            })
+            .collect::<UltraNLPResult<Vec<_>>>()?;
+        // 频率之和在usize::MAX处饱和而不是溢出.
+        let frequency = tags_with_frequencies
+            .iter()
+            .fold(0, |total_frequency: usize, (_, frequency)| {
+                total_frequency.saturating_add(*frequency)
+            });
+        // 频率相同时取靠前的词性.
+        let tag = tags_with_frequencies
+            .iter()
+            .rev()
+            .max_by_key(|(_, frequency)| *frequency)
+            .map(|(tag, _)| *tag);
+
+        Ok((word.to_string(), PatternEntry::new(frequency, tag)))
+    })
+}
+
+fn open_dictionary_file<P: AsRef<Path>>(
+    path: P
+) -> UltraNLPResult<BufReader<File>> {
+    let file = File::open(path)?;
+
+    Ok(BufReader::new(file))
+}
+
+/// Loads a jieba style dictionary file and builds a dictionary from the patterns,
+/// duplicate patterns are reported by their line numbers.
+pub(crate) fn load_jieba_dictionary<D, P: AsRef<Path>, F>(path: P, build: F) -> UltraNLPResult<D>
+where
+    F: FnOnce(Vec<(String, PatternEntry)>) -> UltraNLPResult<D>,
+{
+    let lines = parse_jieba_dictionary_with_line_numbers(open_dictionary_file(path)?)?;
+
+    build_with_line_numbers(lines, JIEBA_DICTIONARY, build)
+}
+
+/// Loads a HanLP style dictionary file, see `load_jieba_dictionary`.
+pub(crate) fn load_hanlp_dictionary<D, P: AsRef<Path>, F>(path: P, build: F) -> UltraNLPResult<D>
+where
+    F: FnOnce(Vec<(String, PatternEntry)>) -> UltraNLPResult<D>,
+{
+    let lines = parse_hanlp_dictionary_with_line_numbers(open_dictionary_file(path)?)?;
+
+    build_with_line_numbers(lines, HANLP_DICTIONARY, build)
+}
+
+fn build_with_line_numbers<D, F>(
+    lines: Vec<(usize, String, PatternEntry)>,
+    format: &'static str,
+    build: F,
+) -> UltraNLPResult<D>
+where
+    F: FnOnce(Vec<(String, PatternEntry)>) -> UltraNLPResult<D>,
+{
+    let line_numbers = lines
+        .iter()
+        .map(|(line, _, _)| *line)
+        .collect::<Vec<_>>();
+
+    // 将模式的序号换成文件中的行号.
+    build(without_line_numbers(lines)).map_err(|err| match err {
+        UltraNLPError::DuplicatePattern { pattern, first_index, second_index } => {
+            UltraNLPError::DuplicateLines {
+                format,
+                pattern,
+                first_line: line_numbers[first_index],
+                second_line: line_numbers[second_index],
+            }
+        },
+        err => err,
+    })
+}
+
+fn without_line_numbers(
+    lines: Vec<(usize, String, PatternEntry)>
+) -> Vec<(String, PatternEntry)> {
+    lines
+        .into_iter()
+        .map(|(_, pattern, entry)| (pattern, entry))
+        .collect()
+}
+
+// 行号从1开始, 空行被跳过, 但仍然计入行号.
+fn parse_lines<R: BufRead, F: Fn(usize, &[&str]) -> UltraNLPResult<(String, PatternEntry)>>(
+    reader: R,
+    parse_line: F,
+) -> UltraNLPResult<Vec<(usize, String, PatternEntry)>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => return Some(Err(UltraNLPError::from(err))),
+            };
+            // 一些词典文件以BOM开头.
+            let line = if index == 0 {
+                line.trim_start_matches('\u{feff}')
+            } else {
+                &line
+            };
+
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            if fields.is_empty() {
+                None
+            } else {
+                Some(
+                    parse_line(index + 1, &fields)
+                        .map(|(pattern, entry)| (index + 1, pattern, entry))
+                )
+            }
+        })
+        .collect()
+}
+
+fn parse_frequency(value: &str) -> Option<usize> {
+    value.parse::<usize>().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use crate::{
+        Dictionary,
+        DictionaryBuilder,
+        PatternEntry,
+        UltraNLPError,
+        parse_hanlp_dictionary,
+        parse_hanlp_dictionary_with_line_numbers,
+        parse_jieba_dictionary,
+        parse_jieba_dictionary_with_line_numbers,
+    };
+    use crate::cedarwood::ForwardDictionary;
+
+    #[test]
+    fn test_parse_jieba_dictionary() {
+        let text = "\u{feff}商品 100 n\n和服 10\n\n服务\n";
+
+        let result = parse_jieba_dictionary(Cursor::new(text)).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                ("商品".to_string(), PatternEntry::new(100, Some("n"))),
+                ("和服".to_string(), PatternEntry::new::<&str>(10, None)),
+                ("服务".to_string(), PatternEntry::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_jieba_dictionary() {
+        let text = "商品 100 n\n和服 n 10\n";
+
+        let result = parse_jieba_dictionary(Cursor::new(text));
+
+        assert!(matches!(
+            result,
+            Err(UltraNLPError::InvalidLine { format: "jieba dictionary", line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_parse_hanlp_dictionary() {
+        let text = "商品 n 100\n和服 n 3 nr 7\n服务\tvn\t5\tv\t5\n";
+
+        let result = parse_hanlp_dictionary(Cursor::new(text)).unwrap();
+
+        assert_eq!(
+            result,
+            vec![
+                ("商品".to_string(), PatternEntry::new(100, Some("n"))),
+                ("和服".to_string(), PatternEntry::new(10, Some("nr"))),
+                ("服务".to_string(), PatternEntry::new(10, Some("vn"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_hanlp_dictionary_with_large_frequencies() {
+        let text = format!("商品 n {} v 10\n", usize::MAX);
+
+        let result = parse_hanlp_dictionary(Cursor::new(text)).unwrap();
+
+        assert_eq!(result[0].1, PatternEntry::new(usize::MAX, Some("n")));
+    }
+
+    #[test]
+    fn test_parse_invalid_hanlp_dictionary() {
+        let text = "商品 n 100 v\n";
+
+        assert!(parse_hanlp_dictionary(Cursor::new(text)).is_err());
+    }
+
+    #[test]
+    fn test_load_jieba() {
+        let path = std::env::temp_dir().join(
+            format!("ultra-nlp-jieba-dictionary-{}.txt", std::process::id())
+        );
+        std::fs::write(&path, "商品 100 n\n服务 50 vn\n").unwrap();
+
+        let result = <ForwardDictionary>::load_jieba(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let dict = result.unwrap();
+        assert_eq!(dict.entry(1).unwrap().tag(), Some("vn"));
+        assert_eq!(dict.total_frequency(), 150);
+    }
+
+    #[test]
+    fn test_parse_with_line_numbers() {
+        let text = "\u{feff}商品 100 n\n\n  \n服务 5\n";
+
+        let jieba_result = parse_jieba_dictionary_with_line_numbers(Cursor::new(text)).unwrap();
+        let hanlp_result = parse_hanlp_dictionary_with_line_numbers(Cursor::new(text));
+
+        assert_eq!(
+            jieba_result
+                .iter()
+                .map(|(line, pattern, _)| (*line, pattern.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, "商品"), (4, "服务")]
+        );
+        assert!(matches!(
+            hanlp_result,
+            Err(UltraNLPError::InvalidLine { format: "HanLP dictionary", line: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_load_jieba_with_duplicate_lines() {
+        let path = std::env::temp_dir().join(
+            format!("ultra-nlp-duplicate-jieba-dictionary-{}.txt", std::process::id())
+        );
+        std::fs::write(&path, "商品 100 n\n\n服务 50 vn\n商品 10\n").unwrap();
+
+        let result = DictionaryBuilder::new().load_jieba::<ForwardDictionary, _>(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(UltraNLPError::DuplicateLines {
+                format: "jieba dictionary",
+                pattern,
+                first_line: 1,
+                second_line: 4,
+            }) if pattern == "商品"
+        ));
+    }
+}
@@ -1,27 +1,171 @@
 use std::fmt;
 use std::error::Error;
+use std::io;
 
 #[derive(Debug)]
-pub struct UltraNLPError {
-    message: String,
+#[non_exhaustive]
+pub enum UltraNLPError {
+    /// No pattern is given to build a dictionary.
+    EmptyPatterns,
+
+    /// A pattern is empty after normalization.
+    EmptyPattern,
+
+    /// Two patterns are the same after normalization.
+    DuplicatePattern {
+        pattern: String,
+        first_index: usize,
+        second_index: usize,
+    },
+
+    /// A pattern is longer than the backend supports.
+    PatternTooLong {
+        pattern_index: usize,
+        length: usize,
+        max_length: usize,
+    },
+
+    /// The number of patterns is more than the backend supports.
+    TooManyPatterns {
+        max_patterns: usize,
+    },
+
+    /// The backend failed to build a dictionary.
+    Backend {
+        backend: &'static str,
+        source: Box<dyn Error + Send + Sync>,
+    },
+
+    /// An empty corpus is given to train a model.
+    EmptyCorpus,
+
+    /// A line of a dictionary file or a model file cannot be parsed,
+    /// the line number starts from 1.
+    InvalidLine {
+        format: &'static str,
+        line: usize,
+    },
+
+    /// Two lines of a dictionary file have the same pattern after normalization,
+    /// the line numbers start from 1.
+    DuplicateLines {
+        format: &'static str,
+        pattern: String,
+        first_line: usize,
+        second_line: usize,
+    },
+
+    /// The bytes are not a valid serialized dictionary.
+    InvalidFormat(String),
+
+    Io(io::Error),
 }
 
 impl UltraNLPError {
-    pub fn new<T: AsRef<str>>(message: T) -> Self {
-        let message = message
-            .as_ref()
-            .to_string();
+    pub(crate) fn backend<E: Error + Send + Sync + 'static>(backend: &'static str, err: E) -> Self {
+        Self::Backend {
+            backend,
+            source: Box::new(err),
+        }
+    }
 
-        Self { message }
+    pub(crate) fn invalid_format<T: AsRef<str>>(message: T) -> Self {
+        Self::InvalidFormat(message.as_ref().to_string())
     }
 }
 
 impl fmt::Display for UltraNLPError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self {
+            Self::EmptyPatterns => write!(f, "The patterns cannot be empty"),
+            Self::EmptyPattern => write!(f, "The pattern cannot be empty"),
+            Self::DuplicatePattern { pattern, first_index, second_index } => write!(
+                f,
+                "The patterns are not unique, {:?} is at {} and {}",
+                pattern,
+                first_index,
+                second_index,
+            ),
+            Self::PatternTooLong { pattern_index, length, max_length } => write!(
+                f,
+                "The pattern at {} is {} bytes, longer than {} bytes",
+                pattern_index,
+                length,
+                max_length,
+            ),
+            Self::TooManyPatterns { max_patterns } => write!(
+                f,
+                "The number of patterns cannot be more than {}",
+                max_patterns,
+            ),
+            Self::Backend { backend, source } => write!(
+                f,
+                "The {} backend failed to build the dictionary: {}",
+                backend,
+                source,
+            ),
+            Self::EmptyCorpus => write!(f, "The corpus cannot be empty"),
+            Self::InvalidLine { format, line } => write!(f, "Invalid {} at line {}", format, line),
+            Self::DuplicateLines { format, pattern, first_line, second_line } => write!(
+                f,
+                "Duplicate {} lines, {:?} is at line {} and {}",
+                format,
+                pattern,
+                first_line,
+                second_line,
+            ),
+            Self::InvalidFormat(message) => write!(f, "{}", message),
+            Self::Io(err) => write!(f, "{}", err),
+        }
     }
 }
 
-impl Error for UltraNLPError {}
+impl Error for UltraNLPError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Backend { source, .. } => Some(source.as_ref()),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for UltraNLPError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
 
 pub type UltraNLPResult<T, E = UltraNLPError> = Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io;
+    use daachorse::CharwiseDoubleArrayAhoCorasick;
+    use daachorse::errors::DaachorseError;
+    use crate::UltraNLPError;
+    use crate::daachorse::BackendError;
+
+    #[test]
+    fn test_source() {
+        let err = UltraNLPError::from(io::Error::new(io::ErrorKind::NotFound, "not found"));
+
+        assert_eq!(err.source().unwrap().to_string(), "not found");
+        assert!(UltraNLPError::EmptyPatterns.source().is_none());
+    }
+
+    #[test]
+    fn test_backend_source() {
+        let daachorse_err = CharwiseDoubleArrayAhoCorasick::<u32>::new(["a", "a"]).err().unwrap();
+        let message = daachorse_err.to_string();
+
+        let err = UltraNLPError::backend("daachorse", BackendError::new(daachorse_err));
+
+        assert_eq!(err.source().unwrap().to_string(), message);
+        assert!(matches!(
+            err.source().unwrap().downcast_ref::<BackendError>().unwrap().inner(),
+            DaachorseError::DuplicatePattern(_)
+        ));
+    }
+}
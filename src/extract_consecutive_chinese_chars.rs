@@ -6,11 +6,9 @@ pub fn extract_consecutive_chinese_chars(text: &str) -> impl Iterator<Item = &st
         static ref RE: Regex = Regex::new(r"\P{Script=Han}+").unwrap();
     }
 
-    let result = RE
+    RE
         .split(text)
-        .filter(|x| !x.is_empty());
-
-    result
+        .filter(|x| !x.is_empty())
 }
 
 #[cfg(test)]
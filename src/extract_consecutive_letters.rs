@@ -6,10 +6,8 @@ pub fn extract_consecutive_letters(text: &str) -> impl Iterator<Item = &str> {
         static ref RE: Regex = Regex::new(r"\p{General_Category=Letter}+").unwrap();
     }
 
-    let result = RE.find_iter(text)
-      .map(|x| x.as_str());
-
-    result
+    RE.find_iter(text)
+      .map(|x| x.as_str())
 }
 
 #[cfg(test)]
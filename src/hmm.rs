@@ -0,0 +1,549 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::{
+    Match,
+    TextRange,
+    BehaviorForUnmatched,
+    UltraNLPResult,
+    UltraNLPError,
+    utils::sorted_entries,
+};
+
+// 未出现过的事件的对数概率.
+const MINIMUM_LOG_PROBABILITY: f64 = -3.14e100;
+
+const STATES: [State; 4] = [State::B, State::M, State::E, State::S];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum State {
+    // 词的开头
+    B,
+    // 词的中间
+    M,
+    // 词的结尾
+    E,
+    // 单字成词
+    S,
+}
+
+impl State {
+    fn index(&self) -> usize {
+        match self {
+            State::B => 0,
+            State::M => 1,
+            State::E => 2,
+            State::S => 3,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            State::B => "B",
+            State::M => "M",
+            State::E => "E",
+            State::S => "S",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "B" => Some(State::B),
+            "M" => Some(State::M),
+            "E" => Some(State::E),
+            "S" => Some(State::S),
+            _ => None,
+        }
+    }
+
+    fn previous_states(&self) -> [State; 2] {
+        match self {
+            State::B => [State::E, State::S],
+            State::M => [State::M, State::B],
+            State::E => [State::B, State::M],
+            State::S => [State::S, State::E],
+        }
+    }
+}
+
+/// A BMES hidden Markov model for recognizing out-of-vocabulary words in Han text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HmmModel {
+    start_log_probabilities: [f64; 4],
+    transition_log_probabilities: [[f64; 4]; 4],
+    emission_log_probabilities: [HashMap<char, f64>; 4],
+}
+
+impl HmmModel {
+    /// Trains a model from a segmented corpus, each sentence is a sequence of words.
+    pub fn train<
+        T: AsRef<str>,
+        S: IntoIterator<Item = T>,
+        I: IntoIterator<Item = S>
+    >(
+        sentences: I
+    ) -> UltraNLPResult<Self> {
+        let mut start_counts = [0usize; 4];
+        let mut transition_counts = [[0usize; 4]; 4];
+        let mut emission_counts: [HashMap<char, usize>; 4] = Default::default();
+
+        sentences
+            .into_iter()
+            .for_each(|words| {
+                let mut previous_state: Option<State> = None;
+
+                words
+                    .into_iter()
+                    .for_each(|word| {
+                        let chars = word.as_ref().chars().collect::<Vec<_>>();
+
+                        chars
+                            .iter()
+                            .enumerate()
+                            .for_each(|(index, char)| {
+                                let state = if chars.len() == 1 {
+                                    State::S
+                                } else if index == 0 {
+                                    State::B
+                                } else if index == chars.len() - 1 {
+                                    State::E
+                                } else {
+                                    State::M
+                                };
+
+                                match previous_state {
+                                    Some(previous_state) => {
+                                        transition_counts[previous_state.index()][state.index()] += 1;
+                                    },
+                                    None => start_counts[state.index()] += 1,
+                                }
+                                *emission_counts[state.index()]
+                                    .entry(*char)
+                                    .or_insert(0) += 1;
+
+                                previous_state = Some(state);
+                            });
+                    });
+            });
+
+        if start_counts.iter().sum::<usize>() == 0 {
+            return Err(UltraNLPError::EmptyCorpus);
+        }
+
+        let start_log_probabilities = to_log_probabilities(&start_counts);
+        let transition_log_probabilities = [
+            to_log_probabilities(&transition_counts[0]),
+            to_log_probabilities(&transition_counts[1]),
+            to_log_probabilities(&transition_counts[2]),
+            to_log_probabilities(&transition_counts[3]),
+        ];
+        let emission_log_probabilities = emission_counts.map(|counts| {
+            let total = counts.values().sum::<usize>() as f64;
+
+            counts
+                .into_iter()
+                .map(|(char, count)| (char, (count as f64 / total).ln()))
+                .collect::<HashMap<_, _>>()
+        });
+
+        Ok(Self {
+            start_log_probabilities,
+            transition_log_probabilities,
+            emission_log_probabilities,
+        })
+    }
+
+    /// Loads a model from a file written by `HmmModel::save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let file = File::open(path)?;
+
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Reads a model from tab-separated lines:
+    ///
+    /// - `start\t<state>\t<log probability>`
+    /// - `transition\t<state>\t<state>\t<log probability>`
+    /// - `emission\t<state>\t<char>\t<log probability>`
+    ///
+    /// States are `B`, `M`, `E` and `S`, empty lines and lines starting with `#` are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> UltraNLPResult<Self> {
+        let mut start_log_probabilities = [MINIMUM_LOG_PROBABILITY; 4];
+        let mut transition_log_probabilities = [[MINIMUM_LOG_PROBABILITY; 4]; 4];
+        let mut emission_log_probabilities: [HashMap<char, f64>; 4] = Default::default();
+
+        reader
+            .lines()
+            .enumerate()
+            .try_for_each(|(index, line)| -> UltraNLPResult<()> {
+                let line = line?;
+                let line = line.trim_end_matches(['\r', '\n']);
+                if line.is_empty() || line.starts_with('#') {
+                    return Ok(());
+                }
+
+                let invalid_line = || {
+                    UltraNLPError::InvalidLine { format: "HMM model", line: index + 1 }
+                };
+                let parse_state = |name: &str| State::from_name(name).ok_or_else(invalid_line);
+                let parse_log_probability = |value: &str| {
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| invalid_line())
+                };
+
+                let fields = line.split('\t').collect::<Vec<_>>();
+                match fields.as_slice() {
+                    ["start", state, log_probability] => {
+                        let state = parse_state(state)?;
+
+                        start_log_probabilities[state.index()] = parse_log_probability(log_probability)?;
+                    },
+                    ["transition", from, to, log_probability] => {
+                        let from = parse_state(from)?;
+                        let to = parse_state(to)?;
+
+                        transition_log_probabilities[from.index()][to.index()] = parse_log_probability(log_probability)?;
+                    },
+                    ["emission", state, char, log_probability] => {
+                        let state = parse_state(state)?;
+                        let mut chars = char.chars();
+                        let char = match (chars.next(), chars.next()) {
+                            (Some(char), None) => char,
+                            _ => return Err(invalid_line()),
+                        };
+
+                        emission_log_probabilities[state.index()]
+                            .insert(char, parse_log_probability(log_probability)?);
+                    },
+                    _ => return Err(invalid_line()),
+                }
+
+                Ok(())
+            })?;
+
+        Ok(Self {
+            start_log_probabilities,
+            transition_log_probabilities,
+            emission_log_probabilities,
+        })
+    }
+
+    /// Saves the model to a file that can be loaded by `HmmModel::load`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        let file = File::create(path)?;
+
+        self.to_writer(file)
+    }
+
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> UltraNLPResult<()> {
+        let mut lines: Vec<String> = vec![];
+
+        STATES
+            .iter()
+            .for_each(|state| {
+                lines.push(format!(
+                    "start\t{}\t{}",
+                    state.name(),
+                    self.start_log_probabilities[state.index()]
+                ));
+            });
+        STATES
+            .iter()
+            .for_each(|from| {
+                STATES
+                    .iter()
+                    .for_each(|to| {
+                        lines.push(format!(
+                            "transition\t{}\t{}\t{}",
+                            from.name(),
+                            to.name(),
+                            self.transition_log_probabilities[from.index()][to.index()]
+                        ));
+                    });
+            });
+        STATES
+            .iter()
+            .for_each(|state| {
+                sorted_entries(&self.emission_log_probabilities[state.index()])
+                    .into_iter()
+                    .for_each(|(char, log_probability)| {
+                        lines.push(format!(
+                            "emission\t{}\t{}\t{}",
+                            state.name(),
+                            char,
+                            log_probability
+                        ));
+                    });
+            });
+
+        lines
+            .into_iter()
+            .try_for_each(|line| writeln!(writer, "{}", line))
+            .map_err(UltraNLPError::from)
+    }
+
+    /// Segments the text into words by the Viterbi algorithm,
+    /// non-Han parts of the text are kept as words.
+    pub fn segment(&self, text: &str) -> Vec<TextRange> {
+        lazy_static! {
+            static ref RE: Regex = Regex::new(r"\p{Script=Han}+").unwrap();
+        }
+
+        let mut results: Vec<TextRange> = vec![];
+
+        let mut last_end_index = 0;
+        RE.find_iter(text)
+            .for_each(|mat| {
+                if mat.start() > last_end_index {
+                    results.push(TextRange::new(last_end_index, mat.start()));
+                }
+
+                results.extend(
+                    self.viterbi(mat.as_str())
+                        .into_iter()
+                        .map(|range| {
+                            TextRange::new(
+                                mat.start() + range.start_index(),
+                                mat.start() + range.end_index(),
+                            )
+                        })
+                );
+
+                last_end_index = mat.end();
+            });
+        if last_end_index < text.len() {
+            results.push(TextRange::new(last_end_index, text.len()));
+        }
+
+        results
+    }
+
+    fn viterbi(&self, text: &str) -> Vec<TextRange> {
+        let chars = text.char_indices().collect::<Vec<_>>();
+        if chars.is_empty() {
+            return vec![];
+        }
+
+        let emission_log_probability_of = |state: State, char: char| -> f64 {
+            self.emission_log_probabilities[state.index()]
+                .get(&char)
+                .copied()
+                .unwrap_or(MINIMUM_LOG_PROBABILITY)
+        };
+
+        // probabilities[i][state]: 第i个字处于state时的最大对数概率.
+        let mut probabilities: Vec<[f64; 4]> = Vec::with_capacity(chars.len());
+        // previous_states[i][state]: 第i个字处于state时, 上一个字的状态.
+        let mut previous_states: Vec<[State; 4]> = Vec::with_capacity(chars.len());
+
+        let (_, first_char) = chars[0];
+        probabilities.push(STATES.map(|state| {
+            self.start_log_probabilities[state.index()]
+                + emission_log_probability_of(state, first_char)
+        }));
+        previous_states.push(STATES);
+        chars
+            .iter()
+            .skip(1)
+            .for_each(|(_, char)| {
+                let last_probabilities = probabilities[probabilities.len() - 1];
+                let mut current_probabilities = [MINIMUM_LOG_PROBABILITY; 4];
+                let mut current_previous_states = STATES;
+
+                STATES
+                    .iter()
+                    .for_each(|state| {
+                        let (probability, previous_state) = state
+                            .previous_states()
+                            .into_iter()
+                            .map(|previous_state| {
+                                let probability = last_probabilities[previous_state.index()]
+                                    + self.transition_log_probabilities[previous_state.index()][state.index()];
+
+                                (probability, previous_state)
+                            })
+                            .max_by(|a, b| a.0.total_cmp(&b.0))
+                            .unwrap();
+
+                        current_probabilities[state.index()] = probability
+                            + emission_log_probability_of(*state, *char);
+                        current_previous_states[state.index()] = previous_state;
+                    });
+
+                probabilities.push(current_probabilities);
+                previous_states.push(current_previous_states);
+            });
+
+        // 最后一个字只能是词的结尾或单字成词.
+        let last_probabilities = probabilities[probabilities.len() - 1];
+        let mut state = if last_probabilities[State::E.index()] >= last_probabilities[State::S.index()] {
+            State::E
+        } else {
+            State::S
+        };
+        let mut states: Vec<State> = vec![state; chars.len()];
+        (1..chars.len())
+            .rev()
+            .for_each(|index| {
+                state = previous_states[index][state.index()];
+                states[index - 1] = state;
+            });
+
+        let mut results: Vec<TextRange> = vec![];
+        let mut word_start_index: Option<usize> = None;
+        chars
+            .iter()
+            .zip(states)
+            .for_each(|((start_index, char), state)| {
+                let end_index = start_index + char.len_utf8();
+
+                match state {
+                    State::B => word_start_index = Some(*start_index),
+                    // 状态转移的约束保证了M之前总有B.
+                    State::M => {},
+                    State::E => {
+                        let start_index = word_start_index
+                            .take()
+                            .unwrap_or(*start_index);
+
+                        results.push(TextRange::new(start_index, end_index));
+                    },
+                    State::S => results.push(TextRange::new(*start_index, end_index)),
+                }
+            });
+        if let Some(start_index) = word_start_index {
+            results.push(TextRange::new(start_index, text.len()));
+        }
+
+        results
+    }
+}
+
+fn to_log_probabilities(counts: &[usize; 4]) -> [f64; 4] {
+    let total = counts.iter().sum::<usize>();
+
+    counts.map(|count| {
+        if count == 0 {
+            MINIMUM_LOG_PROBABILITY
+        } else {
+            (count as f64 / total as f64).ln()
+        }
+    })
+}
+
+/// Splits the unmatched words of results into the words recognized by the HMM,
+/// if the behavior for unmatched is `BehaviorForUnmatched::KeepAsHmmWords`.
+pub(crate) fn recognize_unmatched_words(
+    results: Vec<Match>,
+    text: &str,
+    behavior_for_unmatched: &BehaviorForUnmatched,
+) -> Vec<Match> {
+    match behavior_for_unmatched {
+        BehaviorForUnmatched::KeepAsHmmWords(model) => {
+            results
+                .into_iter()
+                .flat_map(|mat| {
+                    match mat.index_of_patterns() {
+                        Some(_) => vec![mat],
+                        None => {
+                            let range = mat.range();
+
+                            model
+                                .segment(&text[range.start_index()..range.end_index()])
+                                .into_iter()
+                                .map(|x| {
+                                    Match::new(
+                                        TextRange::new(
+                                            range.start_index() + x.start_index(),
+                                            range.start_index() + x.end_index(),
+                                        ),
+                                        None,
+                                    )
+                                })
+                                .collect()
+                        },
+                    }
+                })
+                .collect()
+        },
+        _ => results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HmmModel, UltraNLPError};
+
+    fn create_model() -> HmmModel {
+        HmmModel::train(vec![
+            vec!["我", "来到", "北京", "清华大学"],
+            vec!["他", "来到", "了", "网易", "杭研", "大厦"],
+            vec!["小明", "硕士", "毕业", "于", "中国", "科学院"],
+            vec!["我", "爱", "北京", "天安门"],
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_train_empty_corpus() {
+        let sentences: Vec<Vec<&str>> = vec![];
+
+        assert!(matches!(HmmModel::train(sentences), Err(UltraNLPError::EmptyCorpus)));
+    }
+
+    #[test]
+    fn test_segment() {
+        let text = "我来到北京";
+        let model = create_model();
+
+        let result = model.segment(text);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["我", "来到", "北京"]
+        );
+    }
+
+    #[test]
+    fn test_segment_mixed_text() {
+        let text = "hello北京, world";
+        let model = create_model();
+
+        let result = model.segment(text);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["hello", "北京", ", world"]
+        );
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let model = create_model();
+        let mut buffer: Vec<u8> = vec![];
+
+        model.to_writer(&mut buffer).unwrap();
+        let result = HmmModel::from_reader(buffer.as_slice()).unwrap();
+
+        assert_eq!(result.segment("我来到北京"), model.segment("我来到北京"));
+    }
+
+    #[test]
+    fn test_load_invalid_model() {
+        let text = "start\tX\t-1.0";
+
+        assert!(matches!(
+            HmmModel::from_reader(text.as_bytes()),
+            Err(UltraNLPError::InvalidLine { format: "HMM model", line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use crate::{Match, Normalization, TextRange};
+
+/// A keyword or a key phrase with its score and the ranges of its occurrences in the text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    word: String,
+    score: f64,
+    ranges: Vec<TextRange>,
+}
+
+impl Keyword {
+    pub fn new<T: AsRef<str>>(word: T, score: f64, ranges: Vec<TextRange>) -> Self {
+        Self {
+            word: word.as_ref().to_string(),
+            score,
+            ranges,
+        }
+    }
+
+    /// Returns the normalized word.
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// Returns the score, a higher score means a more important keyword.
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Returns the ranges of the occurrences in the original text.
+    pub fn ranges(&self) -> &[TextRange] {
+        &self.ranges
+    }
+}
+
+/// Extracts the words of the matches from the original text and normalizes them.
+pub(crate) fn normalized_words(
+    text: &str,
+    matches: &[Match],
+    normalization: Normalization,
+) -> Vec<(String, TextRange)> {
+    matches
+        .iter()
+        .filter_map(|mat| {
+            let range = mat.range();
+
+            range
+                .extract(text)
+                .map(|word| (normalization.normalize(word), range))
+        })
+        .collect()
+}
+
+// 单字很少是关键词.
+pub(crate) const DEFAULT_MIN_WORD_CHARS: usize = 2;
+
+/// Checks whether a normalized word can be a keyword,
+/// stopwords, short words and words without letters or digits (e.g. punctuations) cannot.
+pub(crate) fn is_candidate_word(
+    word: &str,
+    stopwords: &HashSet<String>,
+    min_word_chars: usize,
+) -> bool {
+    word.chars().any(char::is_alphanumeric)
+    && word.chars().count() >= min_word_chars
+    && !stopwords.contains(word)
+}
+
+/// Splits the words of the matches into phrase candidates at stopwords, punctuations,
+/// newlines and the unmatched texts between the matches,
+/// each candidate is a sequence of normalized words.
+pub(crate) fn split_phrases(
+    text: &str,
+    matches: &[Match],
+    normalization: Normalization,
+    stopwords: &HashSet<String>,
+) -> Vec<Vec<(String, TextRange)>> {
+    let mut results: Vec<Vec<(String, TextRange)>> = vec![];
+
+    let mut phrase: Vec<(String, TextRange)> = vec![];
+    let mut last_end_index: Option<usize> = None;
+    for (word, range) in normalized_words(text, matches, normalization) {
+        let has_gap = last_end_index
+            .and_then(|end_index| text.get(end_index..range.start_index()))
+            .is_some_and(|gap| !is_blank(gap));
+        last_end_index = Some(range.end_index());
+
+        if has_gap {
+            end_phrase(&mut results, &mut phrase);
+        }
+        // 空白不会分隔短语, 例如英文单词之间的空格.
+        if is_blank(&word) {
+            continue;
+        }
+
+        if stopwords.contains(&word) || !word.chars().any(char::is_alphanumeric) {
+            end_phrase(&mut results, &mut phrase);
+        } else {
+            phrase.push((word, range));
+        }
+    }
+    end_phrase(&mut results, &mut phrase);
+
+    results
+}
+
+fn end_phrase(
+    results: &mut Vec<Vec<(String, TextRange)>>,
+    phrase: &mut Vec<(String, TextRange)>,
+) {
+    if !phrase.is_empty() {
+        results.push(std::mem::take(phrase));
+    }
+}
+
+/// Returns the normalized text covered by the words of a phrase.
+pub(crate) fn phrase_text(
+    text: &str,
+    phrase: &[(String, TextRange)],
+    normalization: Normalization,
+) -> Option<(String, TextRange)> {
+    let (_, first_range) = phrase.first()?;
+    let (_, last_range) = phrase.last()?;
+    let range = TextRange::new(first_range.start_index(), last_range.end_index());
+
+    range
+        .extract(text)
+        .map(|phrase| (normalization.normalize(phrase), range))
+}
+
+fn is_blank(text: &str) -> bool {
+    text
+        .chars()
+        .all(|char| char.is_whitespace() && char != '\n')
+}
+
+/// Sorts the keywords by their scores in descending order and keeps the first `top_k` keywords,
+/// keywords with the same score are ordered by their first occurrences.
+pub(crate) fn select_top_k(mut keywords: Vec<Keyword>, top_k: usize) -> Vec<Keyword> {
+    keywords.sort_by(|a, b| {
+        match b.score.total_cmp(&a.score) {
+            Ordering::Equal => first_start_index(a).cmp(&first_start_index(b)),
+            ordering => ordering,
+        }
+    });
+    keywords.truncate(top_k);
+
+    keywords
+}
+
+fn first_start_index(keyword: &Keyword) -> usize {
+    keyword.ranges
+        .first()
+        .map(|range| range.start_index())
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::{BehaviorForUnmatched, Keyword, Normalization, TextRange};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+    use crate::keyword::{select_top_k, split_phrases};
+
+    #[test]
+    fn test_split_phrases() {
+        let text = "Machine learning and NLP, 自然语言处理\n";
+        let dict = ForwardDictionary::new(
+            vec!["machine", "learning", "and", "nlp", "自然语言", "处理"]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsChars);
+        let stopwords = HashSet::from(["and".to_string()]);
+
+        let result = split_phrases(text, &matches, Normalization::default(), &stopwords);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|phrase| {
+                    phrase
+                        .iter()
+                        .map(|(word, _)| word.as_str())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["machine", "learning"],
+                vec!["nlp"],
+                vec!["自然语言", "处理"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_top_k() {
+        let keywords = vec![
+            Keyword::new("a", 1.0, vec![TextRange::new(0, 1)]),
+            Keyword::new("b", 2.0, vec![TextRange::new(1, 2)]),
+            Keyword::new("c", 2.0, vec![TextRange::new(0, 1), TextRange::new(2, 3)]),
+        ];
+
+        let result = select_top_k(keywords, 2);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["c", "b"]
+        );
+    }
+}
@@ -2,18 +2,60 @@ mod _daachorse;
 mod _cedarwood;
 mod _hashmap;
 mod r#match;
+mod pattern_entry;
+mod removed_pattern;
+mod dictionary;
+mod dictionary_file;
+mod segmenter;
+mod segment_stream;
+mod segment_batch;
 mod text_range;
+mod segmentation;
 mod behavior_for_unmatched;
+mod hmm;
+mod pos_tagger;
+mod stopwords;
+mod keyword;
+mod tf_idf;
+mod text_rank;
+mod rake;
+mod yake;
 mod utils;
+#[cfg(test)]
+mod test_utils;
+mod normalization;
+mod normalized_text;
+mod dag;
+mod serialization;
+mod segments;
 mod error;
 mod ngrams;
 mod extract_consecutive_chinese_chars;
 mod extract_consecutive_letters;
 
 pub use r#match::*;
+pub use pattern_entry::*;
+pub use removed_pattern::*;
+pub use dictionary::*;
+pub use dictionary_file::*;
+pub use segmenter::*;
+pub use segment_stream::*;
+pub use segment_batch::*;
 pub use text_range::*;
+pub use segmentation::*;
 pub use behavior_for_unmatched::*;
+pub use normalization::*;
+pub use normalized_text::*;
+pub use hmm::*;
+pub use pos_tagger::*;
+pub use stopwords::*;
+pub use keyword::*;
+pub use tf_idf::*;
+pub use text_rank::*;
+pub use rake::*;
+pub use yake::*;
 pub use error::*;
+pub use serialization::SerializableValue;
 pub use ngrams::*;
 pub use extract_consecutive_chinese_chars::*;
 pub use extract_consecutive_letters::*;
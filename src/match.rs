@@ -1,4 +1,4 @@
-use crate::TextRange;
+use crate::{Dictionary, TextRange};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Match {
@@ -21,23 +21,110 @@ impl Match {
         self.index_of_patterns
     }
 
+    /// Borrows the value associated with the matched pattern from the dictionary.
+    pub fn value<'d, D: Dictionary>(&self, dict: &'d D) -> Option<&'d D::Value> {
+        self.index_of_patterns.and_then(|index| dict.value(index))
+    }
+
+    /// Attaches the value associated with the matched pattern from the dictionary.
+    pub fn with_value<D: Dictionary>(self, dict: &D) -> ValuedMatch<'_, D::Value> {
+        let value = self.value(dict);
+
+        ValuedMatch { mat: self, value }
+    }
+
     /// A shortcut to get value from map by the index of patterns
+    #[deprecated(note = "create the dictionary with `new_with_values` and use `Match::value`")]
     pub fn value_from<T: Copy>(&self, map: Vec<T>) -> Option<T> {
         match self.index_of_patterns {
             Some(index) => {
-                match map.get(index as usize) {
-                    Some(result) => Some(*result),
-                    None => None
-                }
+                map.get(index).copied()
             }
             None => None
         }
     }
 }
 
+/// A match with the value of the matched pattern, see `Match::with_value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValuedMatch<'d, V> {
+    mat: Match,
+    value: Option<&'d V>,
+}
+
+impl<'d, V> ValuedMatch<'d, V> {
+    pub fn range(&self) -> TextRange {
+        self.mat.range()
+    }
+
+    pub fn index_of_patterns(&self) -> Option<usize> {
+        self.mat.index_of_patterns()
+    }
+
+    /// Returns `None` if the matched content is not in the dictionary.
+    pub fn value(&self) -> Option<&'d V> {
+        self.value
+    }
+
+    pub fn into_match(self) -> Match {
+        self.mat
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod value {
+        use crate::{Match, Normalization, PatternEntry, TextRange};
+        use crate::daachorse::ForwardDictionary;
+
+        #[test]
+        fn test_none() {
+            let dict = ForwardDictionary::new_with_values(
+                vec![("商品", PatternEntry::default(), 1)],
+                Normalization::default(),
+            ).unwrap();
+            let mat = Match::new(TextRange::new(0, 1), None);
+
+            let result = mat.value(&dict);
+
+            assert!(result.is_none());
+        }
+
+        #[test]
+        fn test_some() {
+            let dict = ForwardDictionary::new_with_values(
+                vec![("商品", PatternEntry::default(), "0"), ("服务", PatternEntry::default(), "1")],
+                Normalization::default(),
+            ).unwrap();
+            let mat = Match::new(TextRange::new(0, 1), Some(1));
+
+            let result = mat.value(&dict).unwrap();
+
+            assert_eq!(*result, "1");
+        }
+    }
+
+    mod with_value {
+        use crate::{Match, Normalization, PatternEntry, TextRange};
+        use crate::hashmap::Dictionary;
+
+        #[test]
+        fn test_with_value() {
+            let dict = Dictionary::new_with_values(
+                vec![("商品", PatternEntry::default(), 1), ("服务", PatternEntry::default(), 2)],
+                Normalization::default(),
+            ).unwrap();
+            let mat = Match::new(TextRange::new(0, 1), Some(1));
+
+            let result = mat.clone().with_value(&dict);
+
+            assert_eq!(result.value(), Some(&2));
+            assert_eq!(result.into_match(), mat);
+        }
+    }
+
+    #[allow(deprecated)]
+    mod value_from {
         use crate::{Match, TextRange};
 
         #[test]
@@ -9,7 +9,7 @@ pub fn ngrams(text: &str, n: usize) -> impl Iterator<Item = &str> {
 }
 
 impl<'a> NgramsIterator<'a> {
-    fn new(text: &str, n: usize) -> NgramsIterator {
+    fn new(text: &str, n: usize) -> NgramsIterator<'_> {
         NgramsIterator {
             text,
             n,
@@ -0,0 +1,295 @@
+use caseless::Caseless;
+use unicode_normalization::{is_nfkc_quick, IsNormalized, UnicodeNormalization};
+use unicode_normalization::char::{canonical_combining_class, decompose_compatible};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum CaseNormalization {
+    // 区分大小写.
+    Sensitive,
+    #[default]
+    Lowercase,
+    // Unicode大小写折叠, 例如"ß"与"SS"被视为相同.
+    Fold,
+}
+
+/// The normalization applied to both the patterns of a dictionary and the text to segment.
+///
+/// The default normalization only converts the text to lowercase.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Normalization {
+    case: CaseNormalization,
+    nfkc: bool,
+    full_width_to_half_width: bool,
+    punctuation_to_ascii: bool,
+}
+
+impl Normalization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches the patterns exactly.
+    pub fn case_sensitive() -> Self {
+        Self::new().with_case(CaseNormalization::Sensitive)
+    }
+
+    /// Converts the text to lowercase and maps full-width forms, compatibility characters
+    /// and ideographic punctuations to their canonical forms.
+    pub fn canonical() -> Self {
+        Self::new()
+            .with_nfkc(true)
+            .with_full_width_to_half_width(true)
+            .with_punctuation_to_ascii(true)
+    }
+
+    pub fn with_case(mut self, case: CaseNormalization) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Applies Unicode Normalization Form KC, which also converts full-width forms.
+    pub fn with_nfkc(mut self, nfkc: bool) -> Self {
+        self.nfkc = nfkc;
+        self
+    }
+
+    /// Converts full-width ASCII variants and the ideographic space to their half-width forms.
+    pub fn with_full_width_to_half_width(mut self, full_width_to_half_width: bool) -> Self {
+        self.full_width_to_half_width = full_width_to_half_width;
+        self
+    }
+
+    /// Converts ideographic punctuations such as "。" and "「" to ASCII punctuations.
+    pub fn with_punctuation_to_ascii(mut self, punctuation_to_ascii: bool) -> Self {
+        self.punctuation_to_ascii = punctuation_to_ascii;
+        self
+    }
+
+    pub fn case(&self) -> CaseNormalization {
+        self.case
+    }
+
+    pub fn nfkc(&self) -> bool {
+        self.nfkc
+    }
+
+    pub fn full_width_to_half_width(&self) -> bool {
+        self.full_width_to_half_width
+    }
+
+    pub fn punctuation_to_ascii(&self) -> bool {
+        self.punctuation_to_ascii
+    }
+
+    // 不改变任何文本的规范化.
+    pub(crate) fn is_identity(&self) -> bool {
+        *self == Self::case_sensitive()
+    }
+
+    pub fn normalize(&self, text: &str) -> String {
+        let mut normalized_text = String::with_capacity(text.len());
+        self
+            .chunk_indices(text)
+            .for_each(|(_, chunk)| self.normalize_chunk(chunk, &mut normalized_text));
+
+        normalized_text
+    }
+
+    /// Splits the text into chunks which are normalized independently.
+    ///
+    /// Without NFKC each char is a chunk, otherwise a chunk is a char with
+    /// the following chars which may be composed or reordered with it.
+    pub(crate) fn chunk_indices<'a>(
+        &self,
+        text: &'a str,
+    ) -> impl Iterator<Item = (usize, &'a str)> {
+        let nfkc = self.nfkc;
+        let mut char_indices = text.char_indices().peekable();
+
+        std::iter::from_fn(move || {
+            let (start_index, char) = char_indices.next()?;
+            let mut end_index = start_index + char.len_utf8();
+
+            if nfkc {
+                while let Some((index, char)) = char_indices.next_if(|(_, char)| {
+                    !has_boundary_before(*char)
+                }) {
+                    end_index = index + char.len_utf8();
+                }
+            }
+
+            Some((start_index, &text[start_index..end_index]))
+        })
+    }
+
+    pub(crate) fn normalize_chunk(&self, chunk: &str, output: &mut String) {
+        let mut converted_chunk = String::with_capacity(chunk.len());
+        chunk
+            .chars()
+            .for_each(|char| {
+                let char = if self.full_width_to_half_width {
+                    to_half_width(char)
+                } else {
+                    char
+                };
+
+                match to_ascii_punctuation(char).filter(|_| self.punctuation_to_ascii) {
+                    Some(punctuation) => converted_chunk.push_str(punctuation),
+                    None => converted_chunk.push(char),
+                }
+            });
+        let chars = converted_chunk.chars();
+
+        if self.nfkc {
+            self.push_with_case(chars.nfkc(), output);
+        } else {
+            self.push_with_case(chars, output);
+        }
+    }
+
+    fn push_with_case<I: Iterator<Item = char>>(&self, chars: I, output: &mut String) {
+        match self.case {
+            CaseNormalization::Sensitive => output.extend(chars),
+            // 逐字符转换, 以免结果受上下文影响(例如词尾的"Σ"),
+            // 因此将词尾的"ς"也转换为"σ", 二者的UTF-8长度相同, 不影响位置的映射.
+            CaseNormalization::Lowercase => {
+                output.extend(
+                    chars
+                        .flat_map(char::to_lowercase)
+                        .map(|char| if char == 'ς' { 'σ' } else { char })
+                )
+            },
+            CaseNormalization::Fold => output.extend(chars.default_case_fold()),
+        }
+    }
+}
+
+// 能与之前的字符组合或重排的字符不是块的边界.
+fn has_boundary_before(char: char) -> bool {
+    let mut first_char = None;
+    decompose_compatible(char, |decomposed_char| {
+        first_char.get_or_insert(decomposed_char);
+    });
+    let first_char = first_char.unwrap_or(char);
+
+    canonical_combining_class(first_char) == 0
+    && is_nfkc_quick(std::iter::once(first_char)) == IsNormalized::Yes
+}
+
+fn to_half_width(char: char) -> char {
+    match char {
+        '\u{3000}' => ' ',
+        '\u{ff01}'..='\u{ff5e}' => {
+            // 全角ASCII字符与半角字符的码位相差0xfee0.
+            char::from_u32(char as u32 - 0xfee0).unwrap()
+        },
+        _ => char,
+    }
+}
+
+fn to_ascii_punctuation(char: char) -> Option<&'static str> {
+    match char {
+        '。' | '｡' => Some("."),
+        '、' | '､' => Some(","),
+        '“' | '”' | '「' | '」' | '『' | '』' | '｢' | '｣' => Some("\""),
+        '‘' | '’' => Some("'"),
+        '【' | '〔' => Some("["),
+        '】' | '〕' => Some("]"),
+        '《' | '〈' => Some("<"),
+        '》' | '〉' => Some(">"),
+        '〜' => Some("~"),
+        '—' => Some("-"),
+        '…' => Some("..."),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CaseNormalization, Normalization};
+
+    #[test]
+    fn test_default() {
+        let normalization = Normalization::default();
+
+        let result = normalization.normalize("Hello ＷＯＲＬＤ");
+
+        assert_eq!(result, "hello ｗｏｒｌｄ");
+    }
+
+    #[test]
+    fn test_final_sigma() {
+        let normalization = Normalization::default();
+
+        assert_eq!(
+            normalization.normalize("ΣΊΣΥΦΟΣ"),
+            normalization.normalize("σίσυφος")
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let normalization = Normalization::case_sensitive();
+
+        let result = normalization.normalize("Hello World");
+
+        assert_eq!(result, "Hello World");
+    }
+
+    #[test]
+    fn test_case_fold() {
+        let normalization = Normalization::new().with_case(CaseNormalization::Fold);
+
+        let result = normalization.normalize("Straße");
+
+        assert_eq!(result, "strasse");
+    }
+
+    #[test]
+    fn test_full_width_to_half_width() {
+        let normalization = Normalization::case_sensitive()
+            .with_full_width_to_half_width(true);
+
+        let result = normalization.normalize("ＡＢＣ１２３\u{3000}，");
+
+        assert_eq!(result, "ABC123 ,");
+    }
+
+    #[test]
+    fn test_nfkc() {
+        let normalization = Normalization::case_sensitive().with_nfkc(true);
+
+        let result = normalization.normalize("ｶﾞ①e\u{301}");
+
+        assert_eq!(result, "ガ1é");
+    }
+
+    #[test]
+    fn test_nfkc_chunks() {
+        let normalization = Normalization::new().with_nfkc(true);
+
+        let result = normalization
+            .chunk_indices("ae\u{301}\u{316}Ａ")
+            .collect::<Vec<_>>();
+
+        assert_eq!(result, vec![(0, "a"), (1, "e\u{301}\u{316}"), (6, "Ａ")]);
+    }
+
+    #[test]
+    fn test_punctuation_to_ascii() {
+        let normalization = Normalization::case_sensitive().with_punctuation_to_ascii(true);
+
+        let result = normalization.normalize("他说: 「你好」。……");
+
+        assert_eq!(result, "他说: \"你好\".......");
+    }
+
+    #[test]
+    fn test_canonical() {
+        let normalization = Normalization::canonical();
+
+        let result = normalization.normalize("ＡＢＣ１２３、ｶﾞ①《Ⅻ》");
+
+        assert_eq!(result, "abc123,ガ1<xii>");
+    }
+}
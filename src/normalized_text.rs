@@ -0,0 +1,236 @@
+use crate::{Match, Normalization, TextRange};
+
+/// A normalized copy of a text that remembers where each byte came from,
+/// so ranges found in the normalized copy can be mapped back to the original text.
+///
+/// The segmenters normalize texts by the normalizations of the dictionaries,
+/// use it to process texts by other tools in the same way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NormalizedText {
+    text: String,
+
+    // 规范化后长度或字符边界改变的块, 按位置排序.
+    // 块之间的内容逐字节对应, 位置只相差之前的块累计的长度变化.
+    changed_chunks: Vec<ChangedChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ChangedChunk {
+    normalized_range: TextRange,
+    original_range: TextRange,
+}
+
+impl NormalizedText {
+    pub fn new(text: &str, normalization: Normalization) -> Self {
+        if normalization.is_identity() {
+            return Self {
+                text: text.to_string(),
+                changed_chunks: vec![],
+            };
+        }
+
+        let mut normalized_text = String::with_capacity(text.len());
+        let mut changed_chunks: Vec<ChangedChunk> = vec![];
+
+        normalization
+            .chunk_indices(text)
+            .for_each(|(start_index, chunk)| {
+                let normalized_start_index = normalized_text.len();
+                normalization.normalize_chunk(chunk, &mut normalized_text);
+                let normalized_chunk = &normalized_text[normalized_start_index..];
+
+                if !is_bytewise_mapped(chunk, normalized_chunk) {
+                    changed_chunks.push(ChangedChunk {
+                        normalized_range: TextRange::new(normalized_start_index, normalized_text.len()),
+                        original_range: TextRange::new(start_index, start_index + chunk.len()),
+                    });
+                }
+            });
+
+        Self {
+            text: normalized_text,
+            changed_chunks,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Maps a range of the normalized text to the range of the original text.
+    ///
+    /// A range inside the normalization result of several chars
+    /// is expanded to cover all of them.
+    pub fn to_original_range(&self, range: TextRange) -> TextRange {
+        let start_index = self.to_original_start_index(range.start_index());
+
+        if range.is_empty() {
+            TextRange::new(start_index, start_index)
+        } else {
+            TextRange::new(start_index, self.to_original_end_index(range.end_index()))
+        }
+    }
+
+    pub fn to_original_match(&self, mat: Match) -> Match {
+        Match::new(
+            self.to_original_range(mat.range()),
+            mat.index_of_patterns(),
+        )
+    }
+
+    fn to_original_start_index(&self, index: usize) -> usize {
+        let count = self.changed_chunks
+            .partition_point(|chunk| chunk.normalized_range.end_index() <= index);
+
+        match self.changed_chunks.get(count) {
+            Some(chunk) if chunk.normalized_range.start_index() <= index => {
+                chunk.original_range.start_index()
+            },
+            _ => self.shift_index(count, index),
+        }
+    }
+
+    // 由结束位置之前的最后一个字节所在的块决定.
+    fn to_original_end_index(&self, index: usize) -> usize {
+        let count = self.changed_chunks
+            .partition_point(|chunk| chunk.normalized_range.end_index() < index);
+
+        match self.changed_chunks.get(count) {
+            Some(chunk) if chunk.normalized_range.start_index() < index => {
+                chunk.original_range.end_index()
+            },
+            _ => self.shift_index(count, index),
+        }
+    }
+
+    // 不在任何块中的位置, 按之前的块累计的长度变化平移.
+    fn shift_index(&self, count_of_previous_chunks: usize, index: usize) -> usize {
+        match count_of_previous_chunks.checked_sub(1) {
+            Some(index_of_chunk) => {
+                let chunk = &self.changed_chunks[index_of_chunk];
+
+                index - chunk.normalized_range.end_index() + chunk.original_range.end_index()
+            },
+            None => index,
+        }
+    }
+}
+
+// 内容不变, 或者单个字符变为等长的单个字符时, 逐字节对应仍然保持字符边界.
+fn is_bytewise_mapped(chunk: &str, normalized_chunk: &str) -> bool {
+    chunk.len() == normalized_chunk.len()
+    && (
+        chunk == normalized_chunk
+        || (chunk.chars().nth(1).is_none() && normalized_chunk.chars().nth(1).is_none())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Normalization, TextRange};
+    use crate::normalized_text::NormalizedText;
+
+    #[test]
+    fn test_lowercase() {
+        let text = "Hello 世界";
+
+        let result = NormalizedText::new(text, Normalization::default());
+
+        assert_eq!(result.as_str(), "hello 世界");
+    }
+
+    #[test]
+    fn test_to_original_range_same_length() {
+        let text = "Hello 世界";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        let result = normalized_text.to_original_range(TextRange::new(6, 12));
+
+        assert_eq!(result.extract(text), Some("世界"));
+    }
+
+    #[test]
+    fn test_to_original_range_longer_than_original() {
+        // "İ"(2 bytes)小写后为"i̇"(3 bytes).
+        let text = "İstanbul";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        let result = normalized_text.to_original_range(TextRange::new(3, 10));
+
+        assert_eq!(normalized_text.as_str(), "i̇stanbul");
+        assert_eq!(result.extract(text), Some("stanbul"));
+    }
+
+    #[test]
+    fn test_to_original_range_shorter_than_original() {
+        // "Ⱥ"(2 bytes)小写后为"ⱥ"(3 bytes), "ẞ"(3 bytes)小写后为"ß"(2 bytes).
+        let text = "ẞ和Ⱥ";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        let result = normalized_text.to_original_range(TextRange::new(2, 5));
+
+        assert_eq!(result.extract(text), Some("和"));
+    }
+
+    #[test]
+    fn test_to_original_range_inside_expanded_char() {
+        let text = "İ";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        let result = normalized_text.to_original_range(TextRange::new(0, 1));
+
+        assert_eq!(result.extract(text), Some("İ"));
+    }
+
+    #[test]
+    fn test_to_original_range_composed_chars() {
+        let text = "ae\u{301}Ａ";
+        let normalized_text = NormalizedText::new(text, Normalization::new().with_nfkc(true));
+
+        let result = normalized_text.to_original_range(TextRange::new(1, 4));
+
+        assert_eq!(normalized_text.as_str(), "aéa");
+        assert_eq!(result.extract(text), Some("e\u{301}Ａ"));
+    }
+
+    #[test]
+    fn test_to_original_range_empty() {
+        let text = "İa";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        let result = normalized_text.to_original_range(TextRange::new(4, 4));
+
+        assert_eq!(result, TextRange::new(3, 3));
+    }
+
+    #[test]
+    fn test_identity() {
+        let text = "Hello 世界";
+        let normalized_text = NormalizedText::new(text, Normalization::case_sensitive());
+
+        let result = normalized_text.to_original_range(TextRange::new(6, 12));
+
+        assert_eq!(normalized_text.as_str(), text);
+        assert!(normalized_text.changed_chunks.is_empty());
+        assert_eq!(result.extract(text), Some("世界"));
+    }
+
+    #[test]
+    fn test_only_changed_chunks_are_stored() {
+        let text = "ẞ和Ⱥbc";
+        let normalized_text = NormalizedText::new(text, Normalization::default());
+
+        assert_eq!(normalized_text.changed_chunks.len(), 2);
+    }
+
+    #[test]
+    fn test_to_original_range_canonical() {
+        let text = "ＡＢＣ１２３「商品」";
+        let normalized_text = NormalizedText::new(text, Normalization::canonical());
+
+        let result = normalized_text.to_original_range(TextRange::new(0, 6));
+
+        assert_eq!(normalized_text.as_str(), "abc123\"商品\"");
+        assert_eq!(result.extract(text), Some("ＡＢＣ１２３"));
+    }
+}
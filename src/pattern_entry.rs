@@ -0,0 +1,175 @@
+/// The information attached to a pattern of a dictionary.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatternEntry {
+    frequency: usize,
+    tag: Option<String>,
+}
+
+impl PatternEntry {
+    pub fn new<T: AsRef<str>>(frequency: usize, tag: Option<T>) -> Self {
+        let tag = tag.map(|tag| tag.as_ref().to_string());
+
+        Self { frequency, tag }
+    }
+
+    pub fn frequency(&self) -> usize {
+        self.frequency
+    }
+
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+}
+
+impl Default for PatternEntry {
+    fn default() -> Self {
+        Self::new::<&str>(1, None)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PatternEntries {
+    entries: Vec<PatternEntry>,
+    total_frequency: usize,
+    // 规范化后最长的模式的字符数.
+    max_pattern_chars: usize,
+}
+
+impl PatternEntries {
+    pub fn new(entries: Vec<PatternEntry>, max_pattern_chars: usize) -> Self {
+        let total_frequency = sum_frequencies(&entries);
+
+        Self { entries, total_frequency, max_pattern_chars }
+    }
+
+    pub fn get(&self, index_of_patterns: usize) -> Option<&PatternEntry> {
+        self.entries.get(index_of_patterns)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn push(&mut self, entry: PatternEntry) {
+        self.total_frequency = self.total_frequency.saturating_add(entry.frequency());
+        self.entries.push(entry);
+    }
+
+    /// Replaces the entry at the index, returns the old entry.
+    pub fn replace(&mut self, index_of_patterns: usize, entry: PatternEntry) -> PatternEntry {
+        let old_entry = std::mem::replace(&mut self.entries[index_of_patterns], entry);
+        self.update_total_frequency(&old_entry, self.entries[index_of_patterns].frequency());
+
+        old_entry
+    }
+
+    /// Removes the entry at the index, the last entry takes its index.
+    pub fn swap_remove(&mut self, index_of_patterns: usize) -> PatternEntry {
+        let entry = self.entries.swap_remove(index_of_patterns);
+        self.update_total_frequency(&entry, 0);
+
+        entry
+    }
+
+    fn update_total_frequency(&mut self, old_entry: &PatternEntry, new_frequency: usize) {
+        if self.total_frequency == usize::MAX {
+            // 总频率已经饱和, 无法减去旧的频率, 重新计算.
+            self.total_frequency = sum_frequencies(&self.entries);
+        } else {
+            self.total_frequency = (self.total_frequency - old_entry.frequency())
+                .saturating_add(new_frequency);
+        }
+    }
+
+    pub fn total_frequency(&self) -> usize {
+        self.total_frequency
+    }
+
+    pub fn max_pattern_chars(&self) -> usize {
+        self.max_pattern_chars
+    }
+
+    pub fn set_max_pattern_chars(&mut self, max_pattern_chars: usize) {
+        self.max_pattern_chars = max_pattern_chars;
+    }
+}
+
+// 来自文件的频率可能很大, 总频率在usize::MAX处饱和而不是溢出.
+fn sum_frequencies(entries: &[PatternEntry]) -> usize {
+    entries
+        .iter()
+        .fold(0, |total_frequency: usize, entry| total_frequency.saturating_add(entry.frequency()))
+}
+
+pub(crate) fn with_default_entries<T, I: IntoIterator<Item = T>>(
+    patterns: I
+) -> impl Iterator<Item = (T, PatternEntry)> {
+    patterns
+        .into_iter()
+        .map(|pattern| (pattern, PatternEntry::default()))
+}
+
+pub(crate) fn with_frequency_entries<T, I: IntoIterator<Item = (T, usize)>>(
+    patterns_with_frequencies: I
+) -> impl Iterator<Item = (T, PatternEntry)> {
+    patterns_with_frequencies
+        .into_iter()
+        .map(|(pattern, frequency)| {
+            (pattern, PatternEntry::new::<&str>(frequency, None))
+        })
+}
+
+pub(crate) fn with_tagged_frequency_entries<
+    T,
+    U: AsRef<str>,
+    I: IntoIterator<Item = (T, usize, U)>
+>(
+    patterns_with_tagged_frequencies: I
+) -> impl Iterator<Item = (T, PatternEntry)> {
+    patterns_with_tagged_frequencies
+        .into_iter()
+        .map(|(pattern, frequency, tag)| {
+            (pattern, PatternEntry::new(frequency, Some(tag)))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PatternEntry;
+    use crate::pattern_entry::PatternEntries;
+
+    #[test]
+    fn test_default() {
+        let entry = PatternEntry::default();
+
+        assert_eq!(entry.frequency(), 1);
+        assert_eq!(entry.tag(), None);
+    }
+
+    #[test]
+    fn test_total_frequency() {
+        let entries = PatternEntries::new(vec![
+            PatternEntry::new(10, Some("n")),
+            PatternEntry::new(20, None::<&str>),
+        ], 2);
+
+        assert_eq!(entries.total_frequency(), 30);
+        assert_eq!(entries.get(0).unwrap().tag(), Some("n"));
+        assert!(entries.get(2).is_none());
+    }
+
+    #[test]
+    fn test_saturating_total_frequency() {
+        let mut entries = PatternEntries::new(vec![
+            PatternEntry::new::<&str>(usize::MAX, None),
+            PatternEntry::new::<&str>(10, None),
+        ], 2);
+
+        assert_eq!(entries.total_frequency(), usize::MAX);
+
+        entries.push(PatternEntry::new::<&str>(20, None));
+        entries.swap_remove(0);
+
+        assert_eq!(entries.total_frequency(), 30);
+    }
+}
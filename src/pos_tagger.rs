@@ -0,0 +1,708 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use crate::{
+    Dictionary,
+    Match,
+    TextRange,
+    UltraNLPResult,
+    UltraNLPError,
+    utils::sorted_entries,
+};
+
+/// A matched word with its part-of-speech tag.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedMatch {
+    mat: Match,
+    tag: String,
+}
+
+impl TaggedMatch {
+    pub fn new<T: AsRef<str>>(mat: Match, tag: T) -> Self {
+        Self { mat, tag: tag.as_ref().to_string() }
+    }
+
+    pub fn mat(&self) -> &Match {
+        &self.mat
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.mat.range()
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn into_match(self) -> Match {
+        self.mat
+    }
+}
+
+/// A hidden Markov model over part-of-speech tags.
+///
+/// The candidate tags of a word are the tags it has in the training corpus
+/// and the tag of its dictionary entry, the tags of out-of-vocabulary words
+/// are inferred from the surrounding tags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PosTagger {
+    tags: Vec<String>,
+    indexes_of_tags: HashMap<String, usize>,
+    start_log_probabilities: Vec<f64>,
+    transition_log_probabilities: Vec<Vec<f64>>,
+    emission_log_probabilities: Vec<HashMap<String, f64>>,
+    // 一个词性的词是未登录词的对数概率.
+    unknown_log_probabilities: Vec<f64>,
+}
+
+// Viterbi算法中一个词的候选词性.
+struct Candidate<'a> {
+    tag: &'a str,
+    // 不在模型中的词性(来自字典)没有索引.
+    index_of_tags: Option<usize>,
+    emission_log_probability: f64,
+}
+
+impl PosTagger {
+    /// Trains a tagger from a tagged corpus, each sentence is a sequence of words with their tags.
+    pub fn train<
+        T: AsRef<str>,
+        U: AsRef<str>,
+        S: IntoIterator<Item = (T, U)>,
+        I: IntoIterator<Item = S>
+    >(
+        sentences: I
+    ) -> UltraNLPResult<Self> {
+        let mut tags: Vec<String> = vec![];
+        let mut indexes_of_tags: HashMap<String, usize> = HashMap::new();
+        let mut start_counts: Vec<usize> = vec![];
+        let mut transition_counts: Vec<Vec<usize>> = vec![];
+        let mut emission_counts: Vec<HashMap<String, usize>> = vec![];
+
+        sentences
+            .into_iter()
+            .for_each(|words| {
+                let mut previous_index: Option<usize> = None;
+
+                words
+                    .into_iter()
+                    .for_each(|(word, tag)| {
+                        let index = match indexes_of_tags.get(tag.as_ref()) {
+                            Some(index) => *index,
+                            None => {
+                                let index = tags.len();
+                                tags.push(tag.as_ref().to_string());
+                                indexes_of_tags.insert(tag.as_ref().to_string(), index);
+                                start_counts.push(0);
+                                transition_counts
+                                    .iter_mut()
+                                    .for_each(|counts| counts.push(0));
+                                transition_counts.push(vec![0; index + 1]);
+                                emission_counts.push(HashMap::new());
+
+                                index
+                            },
+                        };
+
+                        match previous_index {
+                            Some(previous_index) => transition_counts[previous_index][index] += 1,
+                            None => start_counts[index] += 1,
+                        }
+                        *emission_counts[index]
+                            .entry(word.as_ref().to_string())
+                            .or_insert(0) += 1;
+
+                        previous_index = Some(index);
+                    });
+            });
+
+        if tags.is_empty() {
+            return Err(UltraNLPError::EmptyCorpus);
+        }
+
+        let start_log_probabilities = to_smoothed_log_probabilities(&start_counts);
+        let transition_log_probabilities = transition_counts
+            .iter()
+            .map(|counts| to_smoothed_log_probabilities(counts))
+            .collect();
+        let unknown_log_probabilities = emission_counts
+            .iter()
+            .map(|counts| {
+                // 以只出现一次的词估计未登录词的概率.
+                let hapax_count = counts
+                    .values()
+                    .filter(|count| **count == 1)
+                    .count();
+                let total = counts.values().sum::<usize>();
+
+                ((hapax_count + 1) as f64 / (total + 1) as f64).ln()
+            })
+            .collect();
+        let emission_log_probabilities = emission_counts
+            .into_iter()
+            .map(|counts| {
+                let total = counts.values().sum::<usize>() as f64;
+
+                counts
+                    .into_iter()
+                    .map(|(word, count)| (word, (count as f64 / total).ln()))
+                    .collect::<HashMap<_, _>>()
+            })
+            .collect();
+
+        Ok(Self {
+            tags,
+            indexes_of_tags,
+            start_log_probabilities,
+            transition_log_probabilities,
+            emission_log_probabilities,
+            unknown_log_probabilities,
+        })
+    }
+
+    /// Loads a tagger from a file written by `PosTagger::save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let file = File::open(path)?;
+
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Reads a tagger from tab-separated lines:
+    ///
+    /// - `tag\t<tag>\t<start log probability>\t<unknown word log probability>`
+    /// - `transition\t<tag>\t<tag>\t<log probability>`
+    /// - `emission\t<tag>\t<word>\t<log probability>`
+    ///
+    /// Tags must be declared before they are used,
+    /// empty lines and lines starting with `#` are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> UltraNLPResult<Self> {
+        let mut tags: Vec<String> = vec![];
+        let mut indexes_of_tags: HashMap<String, usize> = HashMap::new();
+        let mut start_log_probabilities: Vec<f64> = vec![];
+        let mut unknown_log_probabilities: Vec<f64> = vec![];
+        let mut transitions: Vec<(usize, usize, f64)> = vec![];
+        let mut emissions: Vec<(usize, String, f64)> = vec![];
+
+        reader
+            .lines()
+            .enumerate()
+            .try_for_each(|(index, line)| -> UltraNLPResult<()> {
+                let line = line?;
+                let line = line.trim_end_matches(['\r', '\n']);
+                if line.is_empty() || line.starts_with('#') {
+                    return Ok(());
+                }
+
+                let invalid_line = || {
+                    UltraNLPError::InvalidLine { format: "POS tagger", line: index + 1 }
+                };
+                let parse_tag = |tag: &str| {
+                    indexes_of_tags
+                        .get(tag)
+                        .copied()
+                        .ok_or_else(invalid_line)
+                };
+                let parse_log_probability = |value: &str| {
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| invalid_line())
+                };
+
+                let fields = line.split('\t').collect::<Vec<_>>();
+                match fields.as_slice() {
+                    ["tag", tag, start_log_probability, unknown_log_probability] => {
+                        if indexes_of_tags.contains_key(*tag) {
+                            return Err(invalid_line());
+                        }
+                        let start_log_probability = parse_log_probability(start_log_probability)?;
+                        let unknown_log_probability = parse_log_probability(unknown_log_probability)?;
+
+                        indexes_of_tags.insert(tag.to_string(), tags.len());
+                        tags.push(tag.to_string());
+                        start_log_probabilities.push(start_log_probability);
+                        unknown_log_probabilities.push(unknown_log_probability);
+                    },
+                    ["transition", from, to, log_probability] => {
+                        transitions.push((
+                            parse_tag(from)?,
+                            parse_tag(to)?,
+                            parse_log_probability(log_probability)?,
+                        ));
+                    },
+                    ["emission", tag, word, log_probability] => {
+                        emissions.push((
+                            parse_tag(tag)?,
+                            word.to_string(),
+                            parse_log_probability(log_probability)?,
+                        ));
+                    },
+                    _ => return Err(invalid_line()),
+                }
+
+                Ok(())
+            })?;
+
+        if tags.is_empty() {
+            return Err(UltraNLPError::invalid_format("The POS tagger has no tags"));
+        }
+
+        // 未出现的转移视为均匀分布.
+        let uniform_log_probability = -(tags.len() as f64).ln();
+        let mut transition_log_probabilities = vec![
+            vec![uniform_log_probability; tags.len()];
+            tags.len()
+        ];
+        transitions
+            .into_iter()
+            .for_each(|(from, to, log_probability)| {
+                transition_log_probabilities[from][to] = log_probability;
+            });
+        let mut emission_log_probabilities: Vec<HashMap<String, f64>> = vec![HashMap::new(); tags.len()];
+        emissions
+            .into_iter()
+            .for_each(|(index, word, log_probability)| {
+                emission_log_probabilities[index].insert(word, log_probability);
+            });
+
+        Ok(Self {
+            tags,
+            indexes_of_tags,
+            start_log_probabilities,
+            transition_log_probabilities,
+            emission_log_probabilities,
+            unknown_log_probabilities,
+        })
+    }
+
+    /// Saves the tagger to a file that can be loaded by `PosTagger::load`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        let file = File::create(path)?;
+
+        self.to_writer(file)
+    }
+
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> UltraNLPResult<()> {
+        let mut lines: Vec<String> = vec![];
+
+        self.tags
+            .iter()
+            .enumerate()
+            .for_each(|(index, tag)| {
+                lines.push(format!(
+                    "tag\t{}\t{}\t{}",
+                    tag,
+                    self.start_log_probabilities[index],
+                    self.unknown_log_probabilities[index]
+                ));
+            });
+        self.tags
+            .iter()
+            .enumerate()
+            .for_each(|(from_index, from)| {
+                self.tags
+                    .iter()
+                    .enumerate()
+                    .for_each(|(to_index, to)| {
+                        lines.push(format!(
+                            "transition\t{}\t{}\t{}",
+                            from,
+                            to,
+                            self.transition_log_probabilities[from_index][to_index]
+                        ));
+                    });
+            });
+        self.tags
+            .iter()
+            .enumerate()
+            .for_each(|(index, tag)| {
+                sorted_entries(&self.emission_log_probabilities[index])
+                    .into_iter()
+                    .for_each(|(word, log_probability)| {
+                        lines.push(format!("emission\t{}\t{}\t{}", tag, word, log_probability));
+                    });
+            });
+
+        lines
+            .into_iter()
+            .try_for_each(|line| writeln!(writer, "{}", line))
+            .map_err(UltraNLPError::from)
+    }
+
+    /// Returns the tags of the model in the order they first appear in the training corpus.
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Tags the matches of a segmentation of the text,
+    /// the tags of the dictionary entries are used as candidates.
+    ///
+    /// The words are normalized by the normalization of the dictionary before they are looked up,
+    /// so the words of the training corpus should be normalized in the same way.
+    pub fn tag<D: Dictionary>(
+        &self,
+        text: &str,
+        matches: &[Match],
+        dict: &D,
+    ) -> Vec<TaggedMatch> {
+        let normalization = dict.normalization();
+        let words = matches
+            .iter()
+            .map(|mat| {
+                let word = mat
+                    .range()
+                    .extract(text)
+                    .unwrap_or("");
+
+                normalization.normalize(word)
+            })
+            .collect::<Vec<_>>();
+        let words_with_dictionary_tags = matches
+            .iter()
+            .zip(&words)
+            .map(|(mat, word)| {
+                let dictionary_tag = mat
+                    .index_of_patterns()
+                    .and_then(|index| dict.entry(index))
+                    .and_then(|entry| entry.tag());
+
+                (word.as_str(), dictionary_tag)
+            })
+            .collect::<Vec<_>>();
+
+        matches
+            .iter()
+            .zip(self.viterbi(&words_with_dictionary_tags))
+            .map(|(mat, tag)| TaggedMatch::new(mat.clone(), tag))
+            .collect()
+    }
+
+    /// Tags a sequence of words without a dictionary.
+    pub fn tag_words<T: AsRef<str>, I: IntoIterator<Item = T>>(&self, words: I) -> Vec<String> {
+        let words = words
+            .into_iter()
+            .collect::<Vec<_>>();
+        let words_with_dictionary_tags = words
+            .iter()
+            .map(|word| (word.as_ref(), None))
+            .collect::<Vec<_>>();
+
+        self.viterbi(&words_with_dictionary_tags)
+            .into_iter()
+            .map(|tag| tag.to_string())
+            .collect()
+    }
+
+    fn candidates<'a>(&'a self, word: &str, dictionary_tag: Option<&'a str>) -> Vec<Candidate<'a>> {
+        let mut candidates = self.tags
+            .iter()
+            .enumerate()
+            .filter_map(|(index, tag)| {
+                self.emission_log_probabilities[index]
+                    .get(word)
+                    .map(|log_probability| Candidate {
+                        tag,
+                        index_of_tags: Some(index),
+                        emission_log_probability: *log_probability,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(dictionary_tag) = dictionary_tag
+        && candidates.iter().all(|candidate| candidate.tag != dictionary_tag) {
+            let index_of_tags = self.indexes_of_tags.get(dictionary_tag).copied();
+            candidates.push(Candidate {
+                tag: dictionary_tag,
+                index_of_tags,
+                emission_log_probability: index_of_tags
+                    .map(|index| self.unknown_log_probabilities[index])
+                    .unwrap_or_else(|| self.min_unknown_log_probability()),
+            });
+        }
+
+        if candidates.is_empty() {
+            // 未登录词可以是任何词性.
+            self.tags
+                .iter()
+                .enumerate()
+                .map(|(index, tag)| Candidate {
+                    tag,
+                    index_of_tags: Some(index),
+                    emission_log_probability: self.unknown_log_probabilities[index],
+                })
+                .collect()
+        } else {
+            candidates
+        }
+    }
+
+    // 不在模型中的词性取最小的未登录词概率, 以免其胜过语料中的词性.
+    fn min_unknown_log_probability(&self) -> f64 {
+        self.unknown_log_probabilities
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    fn viterbi<'a>(&'a self, words_with_dictionary_tags: &[(&str, Option<&'a str>)]) -> Vec<&'a str> {
+        if words_with_dictionary_tags.is_empty() {
+            return vec![];
+        }
+
+        let lattice = words_with_dictionary_tags
+            .iter()
+            .map(|(word, dictionary_tag)| self.candidates(word, *dictionary_tag))
+            .collect::<Vec<_>>();
+        // 不在模型中的词性与其他词性之间的转移视为均匀分布.
+        let uniform_log_probability = -(self.tags.len() as f64).ln();
+        let start_log_probability_of = |candidate: &Candidate| {
+            candidate.index_of_tags
+                .map(|index| self.start_log_probabilities[index])
+                .unwrap_or(uniform_log_probability)
+        };
+        let transition_log_probability_of = |from: &Candidate, to: &Candidate| {
+            match (from.index_of_tags, to.index_of_tags) {
+                (Some(from), Some(to)) => self.transition_log_probabilities[from][to],
+                _ => uniform_log_probability,
+            }
+        };
+
+        // probabilities[i][j]: 第i个词的词性为第j个候选时的最大对数概率.
+        let mut probabilities: Vec<Vec<f64>> = Vec::with_capacity(lattice.len());
+        // previous_candidates[i][j]: 第i个词的词性为第j个候选时, 上一个词的候选.
+        let mut previous_candidates: Vec<Vec<usize>> = Vec::with_capacity(lattice.len());
+
+        probabilities.push(
+            lattice[0]
+                .iter()
+                .map(|candidate| start_log_probability_of(candidate) + candidate.emission_log_probability)
+                .collect()
+        );
+        previous_candidates.push(vec![0; lattice[0].len()]);
+        (1..lattice.len()).for_each(|index| {
+            let last_probabilities = &probabilities[index - 1];
+            let (current_probabilities, current_previous_candidates): (Vec<_>, Vec<_>) = lattice[index]
+                .iter()
+                .map(|candidate| {
+                    let (probability, previous_candidate) = lattice[index - 1]
+                        .iter()
+                        .enumerate()
+                        .map(|(previous_candidate, previous)| {
+                            let probability = last_probabilities[previous_candidate]
+                                + transition_log_probability_of(previous, candidate);
+
+                            (probability, previous_candidate)
+                        })
+                        .max_by(|a, b| a.0.total_cmp(&b.0))
+                        // 每个词至少有一个候选.
+                        .unwrap();
+
+                    (probability + candidate.emission_log_probability, previous_candidate)
+                })
+                .unzip();
+
+            probabilities.push(current_probabilities);
+            previous_candidates.push(current_previous_candidates);
+        });
+
+        let mut candidate = probabilities[probabilities.len() - 1]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(candidate, _)| candidate)
+            .unwrap();
+        let mut results: Vec<&str> = vec![""; lattice.len()];
+        (0..lattice.len())
+            .rev()
+            .for_each(|index| {
+                results[index] = lattice[index][candidate].tag;
+                candidate = previous_candidates[index][candidate];
+            });
+
+        results
+    }
+}
+
+// 加一平滑, 使未出现的转移仍有可能发生.
+fn to_smoothed_log_probabilities(counts: &[usize]) -> Vec<f64> {
+    let total = counts.iter().sum::<usize>() + counts.len();
+
+    counts
+        .iter()
+        .map(|count| ((count + 1) as f64 / total as f64).ln())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, Normalization, PatternEntry, PosTagger, UltraNLPError};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+
+    fn create_tagger() -> PosTagger {
+        PosTagger::train(vec![
+            vec![("我", "r"), ("爱", "v"), ("北京", "ns")],
+            vec![("他", "r"), ("研究", "v"), ("生命", "n")],
+            vec![("研究", "vn"), ("很", "d"), ("重要", "a")],
+            vec![("我们", "r"), ("的", "u"), ("研究", "vn")],
+            vec![("小明", "nr"), ("爱", "v"), ("上海", "ns")],
+            vec![("他", "r"), ("的", "u"), ("工作", "vn")],
+        ]).unwrap()
+    }
+
+    #[test]
+    fn test_train_empty_corpus() {
+        let sentences: Vec<Vec<(&str, &str)>> = vec![];
+
+        assert!(matches!(PosTagger::train(sentences), Err(UltraNLPError::EmptyCorpus)));
+    }
+
+    #[test]
+    fn test_tag_words() {
+        let tagger = create_tagger();
+
+        let result = tagger.tag_words(vec!["我", "研究", "生命"]);
+
+        assert_eq!(result, vec!["r", "v", "n"]);
+    }
+
+    #[test]
+    fn test_disambiguate_by_context() {
+        let tagger = create_tagger();
+
+        let result = tagger.tag_words(vec!["他", "的", "研究"]);
+
+        assert_eq!(result, vec!["r", "u", "vn"]);
+    }
+
+    #[test]
+    fn test_tag_out_of_vocabulary_words() {
+        let tagger = create_tagger();
+
+        let result = tagger.tag_words(vec!["我", "爱", "广州"]);
+
+        assert_eq!(result, vec!["r", "v", "ns"]);
+    }
+
+    #[test]
+    fn test_tag_with_dictionary() {
+        let text = "小红爱杭州";
+        let dict = ForwardDictionary::new_with_tagged_frequencies(
+            vec![("小红", 1, "nr"), ("爱", 1, "v"), ("杭州", 1, "ns")]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let tagger = create_tagger();
+
+        let result = tagger.tag(text, &matches, &dict);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| (x.range().extract(text).unwrap(), x.tag()))
+                .collect::<Vec<_>>(),
+            vec![("小红", "nr"), ("爱", "v"), ("杭州", "ns")]
+        );
+    }
+
+    #[test]
+    fn test_tag_with_valued_dictionary() {
+        let text = "小红爱杭州";
+        let dict = ForwardDictionary::new_with_values(
+            vec![
+                ("小红", PatternEntry::new(1, Some("nr")), "person"),
+                ("爱", PatternEntry::new(1, Some("v")), "verb"),
+                ("杭州", PatternEntry::new(1, Some("ns")), "place"),
+            ],
+            Normalization::default(),
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let tagger = create_tagger();
+
+        let result = tagger.tag(text, &matches, &dict);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.tag())
+                .collect::<Vec<_>>(),
+            vec!["nr", "v", "ns"]
+        );
+    }
+
+    #[test]
+    fn test_tag_with_tags_not_in_model() {
+        let text = "我爱ultra";
+        let dict = ForwardDictionary::new_with_tagged_frequencies(
+            vec![("我", 1, "r"), ("爱", 1, "v"), ("ultra", 1, "eng")]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let tagger = create_tagger();
+
+        let result = tagger.tag(text, &matches, &dict);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.tag())
+                .collect::<Vec<_>>(),
+            vec!["r", "v", "eng"]
+        );
+    }
+
+    #[test]
+    fn test_dictionary_tags_not_in_model_do_not_beat_corpus_tags() {
+        let text = "他研究生命";
+        let dict = ForwardDictionary::new_with_tagged_frequencies(
+            vec![("他", 1, "r"), ("研究", 1, "x"), ("生命", 1, "n")]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let tagger = create_tagger();
+
+        let result = tagger.tag(text, &matches, &dict);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.tag())
+                .collect::<Vec<_>>(),
+            vec!["r", "v", "n"]
+        );
+    }
+
+    #[test]
+    fn test_tag_normalized_words() {
+        let text = "他研究Rust";
+        let dict = ForwardDictionary::new_with_tagged_frequencies(
+            vec![("他", 1, "r"), ("研究", 1, "v"), ("rust", 1, "x")]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let tagger = PosTagger::train(vec![
+            vec![("他", "r"), ("研究", "v"), ("rust", "n")],
+            vec![("我", "r"), ("研究", "v"), ("生命", "n")],
+        ]).unwrap();
+
+        let result = tagger.tag(text, &matches, &dict);
+
+        assert_eq!(result[2].tag(), "n");
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let tagger = create_tagger();
+        let mut buffer: Vec<u8> = vec![];
+
+        tagger.to_writer(&mut buffer).unwrap();
+        let result = PosTagger::from_reader(buffer.as_slice()).unwrap();
+
+        assert_eq!(result, tagger);
+    }
+
+    #[test]
+    fn test_load_invalid_tagger() {
+        let text = "emission\tn\t生命\t-1.0";
+
+        assert!(matches!(
+            PosTagger::from_reader(text.as_bytes()),
+            Err(UltraNLPError::InvalidLine { format: "POS tagger", line: 1 })
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+use crate::{
+    Keyword,
+    Match,
+    Normalization,
+    TextRange,
+    keyword::{phrase_text, select_top_k, split_phrases},
+};
+
+/// Extracts key phrases from the matches of any segmenter by RAKE (Rapid Automatic Keyword Extraction).
+///
+/// The phrase candidates are split at stopwords and punctuations,
+/// the score of a phrase is the sum of `degree / frequency` of its words.
+#[derive(Debug, Clone)]
+pub struct RakeExtractor {
+    stopwords: HashSet<String>,
+    normalization: Normalization,
+    max_phrase_words: usize,
+}
+
+impl Default for RakeExtractor {
+    fn default() -> Self {
+        Self {
+            stopwords: HashSet::new(),
+            normalization: Normalization::default(),
+            max_phrase_words: 3,
+        }
+    }
+}
+
+impl RakeExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stopwords<T: AsRef<str>, I: IntoIterator<Item = T>>(mut self, stopwords: I) -> Self {
+        self.stopwords = stopwords
+            .into_iter()
+            .map(|stopword| stopword.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Phrase candidates with more words are dropped, defaults to 3.
+    pub fn max_phrase_words(mut self, max_phrase_words: usize) -> Self {
+        self.max_phrase_words = max_phrase_words;
+        self
+    }
+
+    /// Returns the `top_k` phrases with the highest scores,
+    /// the ranges of the matches must refer to `text`.
+    pub fn extract(&self, text: &str, matches: &[Match], top_k: usize) -> Vec<Keyword> {
+        let phrases = split_phrases(text, matches, self.normalization, &self.stopwords)
+            .into_iter()
+            .filter(|phrase| phrase.len() <= self.max_phrase_words)
+            .collect::<Vec<_>>();
+
+        let mut frequencies: HashMap<&str, usize> = HashMap::new();
+        let mut degrees: HashMap<&str, usize> = HashMap::new();
+        phrases
+            .iter()
+            .for_each(|phrase| {
+                phrase
+                    .iter()
+                    .for_each(|(word, _)| {
+                        *frequencies.entry(word).or_insert(0) += 1;
+                        // 词的度数包括它自己.
+                        *degrees.entry(word).or_insert(0) += phrase.len();
+                    });
+            });
+        let word_score = |word: &str| degrees[word] as f64 / frequencies[word] as f64;
+
+        let mut scores_and_ranges_of_phrases: HashMap<String, (f64, Vec<TextRange>)> = HashMap::new();
+        phrases
+            .iter()
+            .for_each(|phrase| {
+                if let Some((text, range)) = phrase_text(text, phrase, self.normalization) {
+                    let score = phrase
+                        .iter()
+                        .map(|(word, _)| word_score(word))
+                        .sum::<f64>();
+
+                    scores_and_ranges_of_phrases
+                        .entry(text)
+                        .or_insert_with(|| (score, vec![]))
+                        .1
+                        .push(range);
+                }
+            });
+
+        let keywords = scores_and_ranges_of_phrases
+            .into_iter()
+            .map(|(phrase, (score, ranges))| Keyword::new(phrase, score, ranges))
+            .collect();
+
+        select_top_k(keywords, top_k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, RakeExtractor};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+
+    #[test]
+    fn test_extract() {
+        let text = "Compatibility of systems of linear constraints. \
+            Criteria of compatibility of a system of linear Diophantine equations.";
+        let dict = ForwardDictionary::new(vec![
+            "compatibility",
+            "of",
+            "systems",
+            "system",
+            "linear",
+            "constraints",
+            "criteria",
+            "a",
+            "diophantine",
+            "equations",
+        ]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsChars);
+
+        let result = RakeExtractor::new()
+            .stopwords(vec!["of", "a"])
+            .extract(text, &matches, 3);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["linear diophantine equations", "linear constraints", "compatibility"]
+        );
+        assert_eq!(result[0].score(), 2.5 + 3.0 + 3.0);
+        assert_eq!(
+            result[1]
+                .ranges()
+                .iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["linear constraints"]
+        );
+    }
+
+    #[test]
+    fn test_extract_with_max_phrase_words() {
+        let text = "程序员使用编程语言编写程序";
+        let dict = ForwardDictionary::new(vec!["程序员", "使用", "编程语言", "编写", "程序"]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+
+        let result = RakeExtractor::new()
+            .stopwords(vec!["使用"])
+            .max_phrase_words(1)
+            .extract(text, &matches, 10);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["程序员"]
+        );
+    }
+}
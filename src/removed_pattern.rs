@@ -0,0 +1,50 @@
+use crate::PatternEntry;
+
+/// A pattern removed from a mutable dictionary.
+///
+/// The last pattern takes the index of the removed pattern,
+/// `moved_index_of_patterns` tells which index of patterns obtained before the removal
+/// now refers to `index_of_patterns`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemovedPattern<V = ()> {
+    entry: PatternEntry,
+    value: V,
+    index_of_patterns: usize,
+
+    // 被移动到index_of_patterns的最后一个模式原先的索引, 删除的是最后一个模式时为None.
+    moved_index_of_patterns: Option<usize>,
+}
+
+impl<V> RemovedPattern<V> {
+    pub(crate) fn new(
+        entry: PatternEntry,
+        value: V,
+        index_of_patterns: usize,
+        moved_index_of_patterns: Option<usize>,
+    ) -> Self {
+        Self { entry, value, index_of_patterns, moved_index_of_patterns }
+    }
+
+    pub fn entry(&self) -> &PatternEntry {
+        &self.entry
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Returns the index of the removed pattern before the removal.
+    pub fn index_of_patterns(&self) -> usize {
+        self.index_of_patterns
+    }
+
+    /// Returns the index before the removal of the pattern which now has `index_of_patterns`,
+    /// or `None` if the removed pattern was the last one and no pattern is moved.
+    pub fn moved_index_of_patterns(&self) -> Option<usize> {
+        self.moved_index_of_patterns
+    }
+
+    pub fn into_entry_and_value(self) -> (PatternEntry, V) {
+        (self.entry, self.value)
+    }
+}
@@ -0,0 +1,178 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use crate::DynSegmenter;
+
+/// Segments the documents across threads, the results are in the order of the documents.
+///
+/// The number of threads is the available parallelism of the machine.
+pub fn segment_batch<T, R, F>(documents: &[T], segment: F) -> Vec<R>
+where
+    T: AsRef<str> + Sync,
+    R: Send,
+    F: Fn(&str) -> R + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1);
+
+    segment_batch_with_threads(documents, threads, segment)
+}
+
+/// Segments the documents across threads by a segmenter chosen at runtime,
+/// `segment` is called with the segmenter and each document.
+pub fn segment_batch_by<T, R, F, V>(
+    documents: &[T],
+    segmenter: &dyn DynSegmenter<V>,
+    segment: F,
+) -> Vec<R>
+where
+    T: AsRef<str> + Sync,
+    R: Send,
+    F: Fn(&dyn DynSegmenter<V>, &str) -> R + Sync,
+{
+    segment_batch(documents, |text| segment(segmenter, text))
+}
+
+/// Segments the documents by the specified number of threads,
+/// the results are in the order of the documents.
+pub fn segment_batch_with_threads<T, R, F>(
+    documents: &[T],
+    threads: usize,
+    segment: F,
+) -> Vec<R>
+where
+    T: AsRef<str> + Sync,
+    R: Send,
+    F: Fn(&str) -> R + Sync,
+{
+    let threads = threads.clamp(1, documents.len().max(1));
+    if threads == 1 {
+        return documents
+            .iter()
+            .map(|document| segment(document.as_ref()))
+            .collect();
+    }
+
+    // 每个线程依次领取下一个文档, 以免个别长文档拖慢其他线程.
+    let next_index = AtomicUsize::new(0);
+    let mut results: Vec<Option<R>> = (0..documents.len())
+        .map(|_| None)
+        .collect();
+
+    thread::scope(|scope| {
+        let handles = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results: Vec<(usize, R)> = vec![];
+
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::Relaxed);
+                        match documents.get(index) {
+                            Some(document) => {
+                                results.push((index, segment(document.as_ref())));
+                            },
+                            None => break,
+                        }
+                    }
+
+                    results
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .for_each(|handle| {
+                handle
+                    .join()
+                    // 将线程中的panic传递给调用者
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err))
+                    .into_iter()
+                    .for_each(|(index, result)| results[index] = Some(result));
+            });
+    });
+
+    results
+        .into_iter()
+        // 所有文档都已被处理
+        .map(|result| result.unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        BehaviorForUnmatched,
+        DynSegmenter,
+        SegmenterDictionaries,
+        segment_batch,
+        segment_batch_by,
+        segment_batch_with_threads,
+    };
+    use crate::hashmap::{self, HashmapSegmenter};
+    use crate::daachorse::{
+        segment_forward_longest,
+        segment_n_best,
+        ForwardDictionary,
+        StandardDictionary,
+    };
+
+    #[test]
+    fn test_segment_batch() {
+        let documents = (0..100)
+            .map(|i| "商品和服务".repeat(i % 7))
+            .collect::<Vec<_>>();
+        let dict = ForwardDictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+
+        let result = segment_batch(&documents, |text| {
+            segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+        });
+
+        assert_eq!(
+            result,
+            documents
+                .iter()
+                .map(|text| {
+                    segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_segment_batch_with_threads() {
+        let documents = vec!["商品和服务", "", "和服"];
+        let dict = StandardDictionary::new(vec!["商品", "和", "和服", "服务"]).unwrap();
+
+        let result = segment_batch_with_threads(&documents, 4, |text| {
+            segment_n_best(text, &dict, 2, BehaviorForUnmatched::KeepAsWords).len()
+        });
+
+        assert_eq!(result, vec![2, 1, 2]);
+    }
+
+    #[test]
+    fn test_segment_batch_by() {
+        let documents = vec!["商品和服务", "", "和服"];
+        let dict = hashmap::Dictionary::new(vec!["商品", "和服", "服务"]).unwrap();
+        let segmenter: Box<dyn DynSegmenter> = Box::new(
+            SegmenterDictionaries::<HashmapSegmenter>::new(&dict, &dict, &dict)
+        );
+
+        let result = segment_batch_by(&documents, segmenter.as_ref(), |segmenter, text| {
+            segmenter.segment_backward_longest(text, BehaviorForUnmatched::KeepAsWords).len()
+        });
+
+        assert_eq!(result, vec![3, 0, 1]);
+    }
+
+    #[test]
+    fn test_empty_documents() {
+        let documents: Vec<&str> = vec![];
+
+        let result = segment_batch(&documents, |text| text.len());
+
+        assert!(result.is_empty());
+    }
+}
@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+use crate::{
+    BehaviorForUnmatched,
+    DynSegmenter,
+    Match,
+    TextRange,
+    normalized_text::NormalizedText,
+};
+
+// 未匹配的内容超过此长度时, 将其切分提交, 以免缓冲区无限增长.
+const MAX_BUFFERED_BYTES: usize = 64 * 1024;
+
+/// Segments the text read from a `BufRead` by forward longest matching.
+///
+/// The ranges of the matches are the absolute byte indexes in the stream.
+/// The results are the same as segmenting the whole text at once,
+/// except that an unmatched content longer than 64 KiB is split into pieces.
+///
+/// The backend is the one of `segmenter`, so it can be chosen at runtime.
+pub fn segment_forward_longest_stream<'a, R: BufRead, V>(
+    reader: R,
+    segmenter: &'a dyn DynSegmenter<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+) -> ForwardLongestStream<'a, R, V> {
+    ForwardLongestStream {
+        reader,
+        segmenter,
+        behavior_for_unmatched,
+        text: String::new(),
+        bytes: vec![],
+        offset: 0,
+        search_index: 0,
+        pending: VecDeque::new(),
+        eof: false,
+    }
+}
+
+pub struct ForwardLongestStream<'a, R, V = ()> {
+    reader: R,
+    segmenter: &'a dyn DynSegmenter<V>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+    // 已读取但尚未提交的文本.
+    text: String,
+    // 已读取但尚未解码的字节, 即被截断的UTF-8字符.
+    bytes: Vec<u8>,
+    // text在流中的起始位置.
+    offset: usize,
+    // text中块的边界, 之前的位置都不是匹配的起点, 之后读取的文本也不会改变这一点.
+    // 每次只需对之后的文本重新分词.
+    search_index: usize,
+    pending: VecDeque<Match>,
+    eof: bool,
+}
+
+impl<R: BufRead, V> ForwardLongestStream<'_, R, V> {
+    fn fill(&mut self) -> io::Result<()> {
+        let buffer = self.reader.fill_buf()?;
+
+        if buffer.is_empty() {
+            self.eof = true;
+
+            if !self.bytes.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not end with valid UTF-8",
+                ));
+            }
+        } else {
+            let length = buffer.len();
+            self.bytes.extend_from_slice(buffer);
+            self.reader.consume(length);
+
+            let valid_length = match std::str::from_utf8(&self.bytes) {
+                Ok(text) => text.len(),
+                Err(err) if err.error_len().is_none() => err.valid_up_to(),
+                Err(err) => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+                },
+            };
+            let bytes = self.bytes.drain(..valid_length).collect::<Vec<_>>();
+            // 已经验证过是有效的UTF-8.
+            self.text.push_str(std::str::from_utf8(&bytes).unwrap());
+        }
+
+        Ok(())
+    }
+
+    /// Commits the results which will not be changed by the following text.
+    fn commit(&mut self) {
+        let end_index = if self.eof {
+            self.text.len()
+        } else {
+            self.committable_end_index()
+        };
+        if end_index == 0 {
+            return;
+        }
+
+        // 提交的结果都不依赖于end_index之后的文本, 因此对前缀重新分词的结果与之相同.
+        let results = self.segmenter.segment_forward_longest(
+            &self.text[..end_index],
+            self.behavior_for_unmatched.clone(),
+        );
+        let offset = self.offset;
+        self.pending.extend(
+            results
+                .into_iter()
+                .map(|mat| {
+                    Match::new(
+                        TextRange::new(
+                            offset + mat.range().start_index(),
+                            offset + mat.range().end_index(),
+                        ),
+                        mat.index_of_patterns(),
+                    )
+                })
+        );
+
+        self.text.drain(..end_index);
+        self.offset += end_index;
+    }
+
+    fn committable_end_index(&mut self) -> usize {
+        let normalization = self.segmenter.normalization();
+        let search_index = self.search_index;
+        let text = &self.text[search_index..];
+        // 最后一块的规范化结果可能随之后的文本改变, 因此只使用之前的文本.
+        let chunk_indices = normalization
+            .chunk_indices(text)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let stable_end_index = chunk_indices
+            .last()
+            .copied()
+            .unwrap_or(0);
+        let normalized_text = NormalizedText::new(&text[..stable_end_index], normalization);
+
+        // 从起点开始的匹配只取决于之后的max_pattern_chars个规范化后的字符.
+        let safe_end_index = normalized_text
+            .as_str()
+            .char_indices()
+            .rev()
+            .nth(self.segmenter.max_pattern_chars().saturating_sub(1))
+            .map(|(index, _)| {
+                normalized_text
+                    .to_original_range(TextRange::new(index, index))
+                    .start_index()
+            })
+            .unwrap_or(0);
+
+        let results = self.segmenter.segment_forward_longest(
+            text,
+            BehaviorForUnmatched::KeepAsWords,
+        );
+        let end_index = results
+            .iter()
+            .map(|mat| mat.range().end_index())
+            .take_while(|end_index| *end_index <= safe_end_index)
+            .last()
+            .unwrap_or(0);
+
+        if end_index > 0 {
+            self.search_index = 0;
+
+            return search_index + end_index;
+        }
+
+        // 第一个匹配之前且在safe_end_index之前的位置都不会成为匹配的起点.
+        let matchless_end_index = results
+            .iter()
+            .find(|mat| mat.index_of_patterns().is_some())
+            .map(|mat| mat.range().start_index())
+            .unwrap_or(text.len())
+            .min(safe_end_index);
+        self.search_index = search_index + chunk_indices
+            .iter()
+            .copied()
+            .take_while(|index| *index <= matchless_end_index)
+            .last()
+            .unwrap_or(0);
+
+        if self.text.len() >= MAX_BUFFERED_BYTES {
+            // search_index之前没有匹配的起点, 因此可以在此处切分未匹配的内容.
+            let end_index = self.search_index;
+            self.search_index = 0;
+
+            end_index
+        } else {
+            0
+        }
+    }
+}
+
+impl<R: BufRead, V> Iterator for ForwardLongestStream<'_, R, V> {
+    type Item = io::Result<Match>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mat) = self.pending.pop_front() {
+                return Some(Ok(mat));
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            if let Err(err) = self.fill() {
+                self.eof = true;
+                self.text.clear();
+
+                return Some(Err(err));
+            }
+
+            self.commit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+    use crate::{
+        BehaviorForUnmatched,
+        DictionaryBuilder,
+        Normalization,
+        SegmenterDictionaries,
+        segment_forward_longest_stream,
+    };
+    use crate::daachorse::{
+        segment_forward_longest,
+        BackwardDictionary,
+        DaachorseSegmenter,
+        ForwardDictionary,
+        StandardDictionary,
+    };
+    use crate::hashmap::{self, Dictionary, HashmapSegmenter};
+
+    fn hashmap_segmenter(dict: &Dictionary) -> SegmenterDictionaries<'_, HashmapSegmenter> {
+        SegmenterDictionaries::new(dict, dict, dict)
+    }
+
+    #[test]
+    fn test_words_spanning_chunks() {
+        let text = "商品和服务, hello world ".repeat(10);
+        let patterns = vec!["商品", "和服", "服务", "hello world"];
+        let standard_dict = StandardDictionary::new(&patterns).unwrap();
+        let forward_dict = ForwardDictionary::new(&patterns).unwrap();
+        let backward_dict = BackwardDictionary::new(&patterns).unwrap();
+        let segmenter = SegmenterDictionaries::<DaachorseSegmenter>::new(
+            &standard_dict,
+            &forward_dict,
+            &backward_dict,
+        );
+        // 每次只读取3个字节, 切断所有中文字符和单词.
+        let reader = BufReader::with_capacity(3, text.as_bytes());
+
+        let result = segment_forward_longest_stream(
+            reader,
+            &segmenter,
+            BehaviorForUnmatched::KeepAsChars,
+        )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            result,
+            segment_forward_longest(&text, &forward_dict, BehaviorForUnmatched::KeepAsChars)
+        );
+    }
+
+    #[test]
+    fn test_absolute_offsets() {
+        let text = "foo bar baz";
+        let dict = Dictionary::new(vec!["bar", "baz"]).unwrap();
+        let reader = BufReader::with_capacity(2, text.as_bytes());
+
+        let result = segment_forward_longest_stream(
+            reader,
+            &hashmap_segmenter(&dict),
+            BehaviorForUnmatched::Ignore,
+        )
+            .map(|x| x.unwrap().range().extract(text).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(result, vec!["bar", "baz"]);
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let bytes: &[u8] = &[0x61, 0xff, 0x62];
+        let dict = Dictionary::new(vec!["a"]).unwrap();
+
+        let result = segment_forward_longest_stream(
+            bytes,
+            &hashmap_segmenter(&dict),
+            BehaviorForUnmatched::KeepAsWords,
+        )
+            .collect::<Result<Vec<_>, _>>();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_long_unmatched_content() {
+        let text = "x".repeat(200 * 1024);
+        let dict = Dictionary::new(vec!["foo"]).unwrap();
+
+        let result = segment_forward_longest_stream(
+            text.as_bytes(),
+            &hashmap_segmenter(&dict),
+            BehaviorForUnmatched::KeepAsWords,
+        )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert!(result.len() > 1);
+        assert_eq!(result.last().unwrap().range().end_index(), text.len());
+    }
+
+    #[test]
+    fn test_nfkc_spanning_chunks() {
+        let text = "ｶﾞｲﾄﾞ, e\u{301}cole ".repeat(10);
+        let dict = DictionaryBuilder::new()
+            .normalization(Normalization::new().with_nfkc(true))
+            .build::<Dictionary, _, _>(vec!["ガイド", "école"])
+            .unwrap();
+        let reader = BufReader::with_capacity(1, text.as_bytes());
+
+        let result = segment_forward_longest_stream(
+            reader,
+            &hashmap_segmenter(&dict),
+            BehaviorForUnmatched::Ignore,
+        )
+            .map(|x| x.unwrap().range().extract(&text).unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(result, ["ｶﾞｲﾄﾞ", "e\u{301}cole"].repeat(10));
+    }
+
+    #[test]
+    fn test_unmatched_content_read_in_small_chunks() {
+        let text = format!("{}foo{}", "x".repeat(20 * 1024), "y".repeat(1024));
+        let dict = Dictionary::new(vec!["foo", "xfoo"]).unwrap();
+        let reader = BufReader::with_capacity(1, text.as_bytes());
+
+        let result = segment_forward_longest_stream(
+            reader,
+            &hashmap_segmenter(&dict),
+            BehaviorForUnmatched::KeepAsWords,
+        )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            result,
+            hashmap::segment_forward_longest(&text, &dict, BehaviorForUnmatched::KeepAsWords)
+        );
+    }
+}
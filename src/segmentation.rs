@@ -0,0 +1,28 @@
+use crate::Match;
+
+/// One of the alternative segmentations of a text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segmentation {
+    matches: Vec<Match>,
+
+    // 分词结果的概率的自然对数, 越大越好.
+    score: f64,
+}
+
+impl Segmentation {
+    pub fn new(matches: Vec<Match>, score: f64) -> Self {
+        Self { matches, score }
+    }
+
+    pub fn matches(&self) -> &[Match] {
+        &self.matches
+    }
+
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    pub fn into_matches(self) -> Vec<Match> {
+        self.matches
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    BehaviorForUnmatched,
+    Dictionary,
+    Match,
+    Normalization,
+    Segmentation,
+};
+
+/// The common interface of the segmenters of all backends,
+/// so that the backend can be chosen by a type parameter.
+///
+/// Backends that do not distinguish the kinds of dictionaries use the same type for
+/// all of them, and ignore the redundant dictionaries.
+///
+/// `V` is the type of the values associated with the patterns of the dictionaries.
+pub trait Segmenter<V = ()> {
+    type StandardDictionary: Dictionary<Value = V>;
+    type ForwardDictionary: Dictionary<Value = V>;
+    type BackwardDictionary: Dictionary<Value = V>;
+
+    fn segment_fully<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_forward_longest<T: AsRef<str>>(
+        text: T,
+        dict: &Self::ForwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_backward_longest<T: AsRef<str>>(
+        text: T,
+        dict: &Self::BackwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_bidirectional_longest<T: AsRef<str>>(
+        text: T,
+        forward_dict: &Self::ForwardDictionary,
+        backward_dict: &Self::BackwardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    /// Both dictionaries must be built from the same patterns in the same order
+    /// with the same normalization, so that their indexes of patterns are consistent.
+    fn segment_for_search<T: AsRef<str>>(
+        text: T,
+        forward_dict: &Self::ForwardDictionary,
+        standard_dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_max_probability<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_n_best<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        n: usize,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Segmentation>;
+
+    fn segment_shortest_path<T: AsRef<str>>(
+        text: T,
+        dict: &Self::StandardDictionary,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+}
+
+/// The object-safe counterpart of `Segmenter`, which segments texts by `&self`
+/// with the dictionaries it holds, so that the backend can be chosen at runtime,
+/// e.g. as a `Box<dyn DynSegmenter>`.
+///
+/// It is `Sync`, so it can be shared by the threads of `segment_batch_by`.
+pub trait DynSegmenter<V = ()>: Sync {
+    fn segment_fully(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_forward_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_backward_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_bidirectional_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_for_search(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_max_probability(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    fn segment_n_best(
+        &self,
+        text: &str,
+        n: usize,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Segmentation>;
+
+    fn segment_shortest_path(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match>;
+
+    /// Returns the value associated with a pattern.
+    fn value(&self, index_of_patterns: usize) -> Option<&V>;
+
+    fn normalization(&self) -> Normalization;
+
+    /// Returns the number of chars of the longest pattern.
+    fn max_pattern_chars(&self) -> usize;
+}
+
+/// Borrows the dictionaries of the backend `S` to implement `DynSegmenter`.
+///
+/// The dictionaries must be built from the same patterns in the same order
+/// with the same normalization, see `Segmenter::segment_for_search`.
+pub struct SegmenterDictionaries<'d, S: Segmenter<V>, V = ()> {
+    standard_dict: &'d S::StandardDictionary,
+    forward_dict: &'d S::ForwardDictionary,
+    backward_dict: &'d S::BackwardDictionary,
+}
+
+impl<'d, S: Segmenter<V>, V> SegmenterDictionaries<'d, S, V> {
+    pub fn new(
+        standard_dict: &'d S::StandardDictionary,
+        forward_dict: &'d S::ForwardDictionary,
+        backward_dict: &'d S::BackwardDictionary,
+    ) -> Self {
+        Self { standard_dict, forward_dict, backward_dict }
+    }
+}
+
+// 手动实现, 以免要求S实现Clone和Copy.
+impl<S: Segmenter<V>, V> Clone for SegmenterDictionaries<'_, S, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S: Segmenter<V>, V> Copy for SegmenterDictionaries<'_, S, V> {}
+
+impl<S, V> DynSegmenter<V> for SegmenterDictionaries<'_, S, V>
+where
+    S: Segmenter<V>,
+    S::StandardDictionary: Sync,
+    S::ForwardDictionary: Sync,
+    S::BackwardDictionary: Sync,
+{
+    fn segment_fully(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_fully(text, self.standard_dict, behavior_for_unmatched)
+    }
+
+    fn segment_forward_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_forward_longest(text, self.forward_dict, behavior_for_unmatched)
+    }
+
+    fn segment_backward_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_backward_longest(text, self.backward_dict, behavior_for_unmatched)
+    }
+
+    fn segment_bidirectional_longest(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_bidirectional_longest(
+            text,
+            self.forward_dict,
+            self.backward_dict,
+            behavior_for_unmatched,
+        )
+    }
+
+    fn segment_for_search(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_for_search(
+            text,
+            self.forward_dict,
+            self.standard_dict,
+            behavior_for_unmatched,
+        )
+    }
+
+    fn segment_max_probability(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_max_probability(text, self.standard_dict, behavior_for_unmatched)
+    }
+
+    fn segment_n_best(
+        &self,
+        text: &str,
+        n: usize,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Segmentation> {
+        S::segment_n_best(text, self.standard_dict, n, behavior_for_unmatched)
+    }
+
+    fn segment_shortest_path(
+        &self,
+        text: &str,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Vec<Match> {
+        S::segment_shortest_path(text, self.standard_dict, behavior_for_unmatched)
+    }
+
+    fn value(&self, index_of_patterns: usize) -> Option<&V> {
+        self.forward_dict.value(index_of_patterns)
+    }
+
+    fn normalization(&self) -> Normalization {
+        self.forward_dict.normalization()
+    }
+
+    fn max_pattern_chars(&self) -> usize {
+        self.forward_dict.max_pattern_chars()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, DynSegmenter, SegmenterDictionaries};
+    use crate::cedarwood::{self, CedarwoodSegmenter};
+    use crate::daachorse::{self, DaachorseSegmenter};
+    use crate::hashmap::{self, HashmapSegmenter};
+
+    #[test]
+    fn test_choose_backend_at_runtime() {
+        let text = "商品和服务";
+        let patterns = vec!["商品", "和服", "服务"];
+        let daachorse_standard_dict = daachorse::StandardDictionary::new(&patterns).unwrap();
+        let daachorse_forward_dict = daachorse::ForwardDictionary::new(&patterns).unwrap();
+        let daachorse_backward_dict = daachorse::BackwardDictionary::new(&patterns).unwrap();
+        let cedarwood_forward_dict = cedarwood::ForwardDictionary::new(&patterns).unwrap();
+        let cedarwood_backward_dict = cedarwood::BackwardDictionary::new(&patterns).unwrap();
+        let hashmap_dict = hashmap::Dictionary::new(&patterns).unwrap();
+
+        let segmenters: Vec<Box<dyn DynSegmenter>> = vec![
+            Box::new(SegmenterDictionaries::<DaachorseSegmenter>::new(
+                &daachorse_standard_dict,
+                &daachorse_forward_dict,
+                &daachorse_backward_dict,
+            )),
+            Box::new(SegmenterDictionaries::<CedarwoodSegmenter>::new(
+                &cedarwood_forward_dict,
+                &cedarwood_forward_dict,
+                &cedarwood_backward_dict,
+            )),
+            Box::new(SegmenterDictionaries::<HashmapSegmenter>::new(
+                &hashmap_dict,
+                &hashmap_dict,
+                &hashmap_dict,
+            )),
+        ];
+
+        segmenters
+            .iter()
+            .for_each(|segmenter| {
+                assert_eq!(
+                    segmenter
+                        .segment_forward_longest(text, BehaviorForUnmatched::KeepAsWords)
+                        .into_iter()
+                        .map(|x| x.range().extract(text).unwrap())
+                        .collect::<Vec<_>>(),
+                    vec!["商品", "和服", "务"]
+                );
+                assert_eq!(
+                    segmenter
+                        .segment_backward_longest(text, BehaviorForUnmatched::KeepAsWords)
+                        .into_iter()
+                        .map(|x| x.range().extract(text).unwrap())
+                        .collect::<Vec<_>>(),
+                    vec!["商品", "和", "服务"]
+                );
+                assert_eq!(segmenter.max_pattern_chars(), 2);
+            });
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use crate::{
+    Match,
+    TextRange,
+    BehaviorForUnmatched,
+    utils::split_as_char_ranges,
+    normalized_text::NormalizedText,
+    hmm::recognize_unmatched_words,
+};
+
+/// A source of the matched words in a normalized text.
+///
+/// The unmatched contents are the gaps between the end of all previous matched words
+/// and the start of the next matched word.
+pub(crate) trait MatchSource {
+    fn next_match(&mut self, text: &str) -> Option<Match>;
+
+    /// The range of the text covered by the match returned last,
+    /// which is larger than the match when the match is inside a longer word.
+    fn covered_range(&self, mat: &Match) -> TextRange {
+        mat.range()
+    }
+}
+
+/// The lazy segmentation of a text,
+/// which fills the unmatched contents between the matched words of a `MatchSource`
+/// and maps them back to the original text.
+pub(crate) struct Segments<S> {
+    source: S,
+    normalized_text: Arc<NormalizedText>,
+    behavior_for_unmatched: BehaviorForUnmatched,
+    maximum_matched_end_index: usize,
+    // 已产生但尚未返回的结果, 位置相对于规范化文本.
+    pending: VecDeque<Match>,
+    finished: bool,
+}
+
+impl<S: MatchSource> Segments<S> {
+    pub fn new(
+        source: S,
+        normalized_text: Arc<NormalizedText>,
+        behavior_for_unmatched: BehaviorForUnmatched,
+    ) -> Self {
+        Self {
+            source,
+            normalized_text,
+            behavior_for_unmatched,
+            maximum_matched_end_index: 0,
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    fn push_unmatched(&mut self, range: TextRange) {
+        let text = self.normalized_text.as_str();
+
+        match &self.behavior_for_unmatched {
+            BehaviorForUnmatched::Ignore => {},
+            BehaviorForUnmatched::KeepAsChars => {
+                let iter = split_as_char_ranges(
+                    &text[range.start_index()..range.end_index()]
+                )
+                    .map(|x| {
+                        Match::new(
+                            TextRange::new(
+                                range.start_index() + x.start_index(),
+                                range.start_index() + x.end_index(),
+                            ),
+                            None,
+                        )
+                    });
+
+                self.pending.extend(iter);
+            },
+            | BehaviorForUnmatched::KeepAsWords
+            | BehaviorForUnmatched::KeepAsHmmWords(_) => {
+                let results = recognize_unmatched_words(
+                    vec![Match::new(range, None)],
+                    text,
+                    &self.behavior_for_unmatched,
+                );
+
+                self.pending.extend(results);
+            },
+        }
+    }
+}
+
+impl<S: MatchSource> Iterator for Segments<S> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mat) = self.pending.pop_front() {
+                return Some(self.normalized_text.to_original_match(mat));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            let text_len = self.normalized_text.as_str().len();
+            match self.source.next_match(self.normalized_text.as_str()) {
+                Some(mat) => {
+                    let range = self.source.covered_range(&mat);
+
+                    if range.start_index() > self.maximum_matched_end_index {
+                        // 处理匹配结果之前的文本
+                        self.push_unmatched(TextRange::new(
+                            self.maximum_matched_end_index,
+                            range.start_index(),
+                        ));
+                    }
+
+                    if range.end_index() > self.maximum_matched_end_index {
+                        self.maximum_matched_end_index = range.end_index();
+                    }
+
+                    self.pending.push_back(mat);
+                },
+                None => {
+                    self.finished = true;
+
+                    if self.maximum_matched_end_index < text_len {
+                        // 处理text剩余的文本
+                        self.push_unmatched(TextRange::new(
+                            self.maximum_matched_end_index,
+                            text_len,
+                        ));
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,488 @@
+use std::fs;
+use std::path::Path;
+use crate::{
+    CaseNormalization,
+    Normalization,
+    PatternEntry,
+    UltraNLPError,
+    UltraNLPResult,
+    pattern_entry::PatternEntries,
+};
+
+const MAGIC: &[u8; 8] = b"ULTRANLP";
+const FORMAT_VERSION: u32 = 4;
+// magic, 格式版本, 字典类型, usize的字节数, 负载长度.
+const HEADER_LENGTH: usize = 8 + 4 + 1 + 1 + 8;
+const CHECKSUM_LENGTH: usize = 4;
+
+/// The kinds of serialized dictionaries, a dictionary can only be loaded as its own kind.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum DictionaryKind {
+    DaachorseStandard = 1,
+    DaachorseForward = 2,
+    DaachorseBackward = 3,
+    CedarwoodForward = 4,
+    CedarwoodBackward = 5,
+    Hashmap = 6,
+}
+
+/// Wraps the payload with the header and the checksum.
+pub(crate) fn encode(kind: DictionaryKind, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LENGTH + payload.len() + CHECKSUM_LENGTH);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.push(kind as u8);
+    bytes.push(size_of::<usize>() as u8);
+    bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(payload);
+
+    let checksum = crc32fast::hash(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+
+    bytes
+}
+
+/// Verifies the header and the checksum, returns the payload.
+pub(crate) fn decode(kind: DictionaryKind, bytes: &[u8]) -> UltraNLPResult<&[u8]> {
+    if bytes.len() < HEADER_LENGTH + CHECKSUM_LENGTH || &bytes[..8] != MAGIC {
+        return Err(UltraNLPError::invalid_format("The bytes are not a serialized dictionary"));
+    }
+
+    let mut decoder = Decoder::new(&bytes[8..HEADER_LENGTH]);
+    let version = decoder.read_u32()?;
+    if version != FORMAT_VERSION {
+        return Err(UltraNLPError::invalid_format(format!(
+            "The format version {} is not supported",
+            version
+        )));
+    }
+    if decoder.read_u8()? != kind as u8 {
+        return Err(UltraNLPError::invalid_format("The kind of the dictionary does not match"));
+    }
+    if decoder.read_u8()? as usize != size_of::<usize>() {
+        return Err(UltraNLPError::invalid_format(
+            "The dictionary was serialized on a platform with a different pointer width"
+        ));
+    }
+    let payload_length = decoder.read_len()?;
+    if bytes.len() - HEADER_LENGTH - CHECKSUM_LENGTH != payload_length {
+        return Err(UltraNLPError::invalid_format("The length of the dictionary does not match"));
+    }
+
+    let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LENGTH);
+    // 长度已经检查过
+    let checksum = u32::from_le_bytes(checksum.try_into().unwrap());
+    if crc32fast::hash(content) != checksum {
+        return Err(UltraNLPError::invalid_format("The checksum of the dictionary does not match"));
+    }
+
+    Ok(&content[HEADER_LENGTH..])
+}
+
+pub(crate) fn write_file<P: AsRef<Path>>(path: P, bytes: &[u8]) -> UltraNLPResult<()> {
+    fs::write(path, bytes)
+        .map_err(UltraNLPError::from)
+}
+
+pub(crate) fn read_file<P: AsRef<Path>>(path: P) -> UltraNLPResult<Vec<u8>> {
+    fs::read(path)
+        .map_err(UltraNLPError::from)
+}
+
+/// Checks the number of chars of the longest pattern read from the bytes,
+/// a wrong one would make the longest matching miss patterns.
+pub(crate) fn validate_max_pattern_chars(
+    patterns: &[String],
+    entries: &PatternEntries,
+) -> UltraNLPResult<()> {
+    let max_pattern_chars = patterns
+        .iter()
+        .map(|pattern| pattern.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    if max_pattern_chars == entries.max_pattern_chars() {
+        Ok(())
+    } else {
+        Err(UltraNLPError::invalid_format("The length of the longest pattern does not match"))
+    }
+}
+
+/// The values which can be serialized with a dictionary, see `to_bytes` of the dictionaries.
+pub trait SerializableValue: Sized {
+    /// Appends the bytes of the value.
+    fn write_bytes(&self, bytes: &mut Vec<u8>);
+
+    /// Reads the value from the bytes appended by `write_bytes`.
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self>;
+}
+
+impl SerializableValue for () {
+    fn write_bytes(&self, _bytes: &mut Vec<u8>) {}
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        if bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(UltraNLPError::invalid_format("The value is invalid"))
+        }
+    }
+}
+
+impl SerializableValue for bool {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        bytes.push(*self as u8);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        match bytes {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(UltraNLPError::invalid_format("The value is invalid")),
+        }
+    }
+}
+
+macro_rules! impl_serializable_value_for_numbers {
+    ($($number:ty),*) => {
+        $(
+            impl SerializableValue for $number {
+                fn write_bytes(&self, bytes: &mut Vec<u8>) {
+                    bytes.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+                    bytes
+                        .try_into()
+                        .map(Self::from_le_bytes)
+                        .map_err(|_| UltraNLPError::invalid_format("The value is invalid"))
+                }
+            }
+        )*
+    };
+}
+
+// usize和isize的字节数已由头部保证一致.
+impl_serializable_value_for_numbers!(
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    f32, f64
+);
+
+impl SerializableValue for char {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        (*self as u32).write_bytes(bytes);
+    }
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        char::from_u32(u32::read_bytes(bytes)?)
+            .ok_or_else(|| UltraNLPError::invalid_format("The value is invalid"))
+    }
+}
+
+impl SerializableValue for String {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(self.as_bytes());
+    }
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        String::from_utf8(bytes.to_vec())
+            .map_err(|err| UltraNLPError::invalid_format(err.to_string()))
+    }
+}
+
+impl<T: SerializableValue> SerializableValue for Option<T> {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        if let Some(value) = self {
+            bytes.push(1);
+            value.write_bytes(bytes);
+        } else {
+            bytes.push(0);
+        }
+    }
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        match bytes.split_first() {
+            Some((0, [])) => Ok(None),
+            Some((1, value)) => T::read_bytes(value).map(Some),
+            _ => Err(UltraNLPError::invalid_format("The value is invalid")),
+        }
+    }
+}
+
+impl<T: SerializableValue> SerializableValue for Vec<T> {
+    fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        let mut encoder = Encoder::new();
+        encoder.write_values(self);
+        bytes.extend_from_slice(&encoder.into_bytes());
+    }
+
+    fn read_bytes(bytes: &[u8]) -> UltraNLPResult<Self> {
+        let mut decoder = Decoder::new(bytes);
+        let values = decoder.read_values()?;
+        if !decoder.is_empty() {
+            return Err(UltraNLPError::invalid_format("The value is invalid"));
+        }
+
+        Ok(values)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_len(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    pub fn write_bytes(&mut self, value: &[u8]) {
+        self.write_len(value.len());
+        self.bytes.extend_from_slice(value);
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_entries(&mut self, entries: &PatternEntries) {
+        self.write_len(entries.len());
+        (0..entries.len())
+            .filter_map(|index| entries.get(index))
+            .for_each(|entry| {
+                self.write_len(entry.frequency());
+                match entry.tag() {
+                    Some(tag) => {
+                        self.write_u8(1);
+                        self.write_str(tag);
+                    },
+                    None => self.write_u8(0),
+                }
+            });
+        self.write_len(entries.max_pattern_chars());
+    }
+
+    pub fn write_normalization(&mut self, normalization: Normalization) {
+        self.write_u8(match normalization.case() {
+            CaseNormalization::Sensitive => 0,
+            CaseNormalization::Lowercase => 1,
+            CaseNormalization::Fold => 2,
+        });
+        self.write_u8(normalization.nfkc() as u8);
+        self.write_u8(normalization.full_width_to_half_width() as u8);
+        self.write_u8(normalization.punctuation_to_ascii() as u8);
+    }
+
+    pub fn write_values<V: SerializableValue>(&mut self, values: &[V]) {
+        self.write_len(values.len());
+        values
+            .iter()
+            .for_each(|value| {
+                let mut bytes = vec![];
+                value.write_bytes(&mut bytes);
+                self.write_bytes(&bytes);
+            });
+    }
+
+    /// Writes the patterns with their values.
+    pub fn write_patterns<T: AsRef<str>, I: IntoIterator<Item = (T, usize)>>(
+        &mut self,
+        patterns_with_values: I,
+    ) {
+        let patterns_with_values = patterns_with_values
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        self.write_len(patterns_with_values.len());
+        patterns_with_values
+            .iter()
+            .for_each(|(pattern, value)| {
+                self.write_str(pattern.as_ref());
+                self.write_len(*value);
+            });
+    }
+}
+
+pub(crate) struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn read_slice(&mut self, length: usize) -> UltraNLPResult<&'a [u8]> {
+        if self.bytes.len() < length {
+            return Err(UltraNLPError::invalid_format("The dictionary is truncated"));
+        }
+
+        let (slice, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+
+        Ok(slice)
+    }
+
+    pub fn read_u8(&mut self) -> UltraNLPResult<u8> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    pub fn read_u32(&mut self) -> UltraNLPResult<u32> {
+        // 长度已经检查过
+        Ok(u32::from_le_bytes(self.read_slice(4)?.try_into().unwrap()))
+    }
+
+    pub fn read_u64(&mut self) -> UltraNLPResult<u64> {
+        // 长度已经检查过
+        Ok(u64::from_le_bytes(self.read_slice(8)?.try_into().unwrap()))
+    }
+
+    pub fn read_len(&mut self) -> UltraNLPResult<usize> {
+        usize::try_from(self.read_u64()?)
+            .map_err(|err| UltraNLPError::invalid_format(err.to_string()))
+    }
+
+    pub fn read_bytes(&mut self) -> UltraNLPResult<&'a [u8]> {
+        let length = self.read_len()?;
+
+        self.read_slice(length)
+    }
+
+    pub fn read_str(&mut self) -> UltraNLPResult<&'a str> {
+        std::str::from_utf8(self.read_bytes()?)
+            .map_err(|err| UltraNLPError::invalid_format(err.to_string()))
+    }
+
+    pub fn read_entries(&mut self) -> UltraNLPResult<PatternEntries> {
+        let length = self.read_len()?;
+        let entries = (0..length)
+            .map(|_| {
+                let frequency = self.read_len()?;
+                let tag = match self.read_u8()? {
+                    0 => None,
+                    _ => Some(self.read_str()?),
+                };
+
+                Ok(PatternEntry::new(frequency, tag))
+            })
+            .collect::<UltraNLPResult<Vec<_>>>()?;
+        let max_pattern_chars = self.read_len()?;
+
+        Ok(PatternEntries::new(entries, max_pattern_chars))
+    }
+
+    pub fn read_normalization(&mut self) -> UltraNLPResult<Normalization> {
+        let case = match self.read_u8()? {
+            0 => CaseNormalization::Sensitive,
+            1 => CaseNormalization::Lowercase,
+            2 => CaseNormalization::Fold,
+            _ => return Err(UltraNLPError::invalid_format("The normalization is invalid")),
+        };
+        let nfkc = self.read_u8()? != 0;
+        let full_width_to_half_width = self.read_u8()? != 0;
+        let punctuation_to_ascii = self.read_u8()? != 0;
+
+        Ok(
+            Normalization::new()
+                .with_case(case)
+                .with_nfkc(nfkc)
+                .with_full_width_to_half_width(full_width_to_half_width)
+                .with_punctuation_to_ascii(punctuation_to_ascii)
+        )
+    }
+
+    pub fn read_values<V: SerializableValue>(&mut self) -> UltraNLPResult<Vec<V>> {
+        let length = self.read_len()?;
+
+        (0..length)
+            .map(|_| V::read_bytes(self.read_bytes()?))
+            .collect()
+    }
+
+    pub fn read_patterns(&mut self) -> UltraNLPResult<Vec<(&'a str, usize)>> {
+        let length = self.read_len()?;
+
+        (0..length)
+            .map(|_| {
+                let pattern = self.read_str()?;
+                let value = self.read_len()?;
+
+                Ok((pattern, value))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SerializableValue;
+    use crate::serialization::{decode, encode, DictionaryKind};
+
+    #[test]
+    fn test_encode_and_decode() {
+        let bytes = encode(DictionaryKind::Hashmap, b"payload");
+
+        let result = decode(DictionaryKind::Hashmap, &bytes).unwrap();
+
+        assert_eq!(result, b"payload");
+    }
+
+    #[test]
+    fn test_decode_with_wrong_kind() {
+        let bytes = encode(DictionaryKind::Hashmap, b"payload");
+
+        assert!(decode(DictionaryKind::CedarwoodForward, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_corrupted_bytes() {
+        let mut bytes = encode(DictionaryKind::Hashmap, b"payload");
+        let index = bytes.len() - 5;
+        bytes[index] ^= 1;
+
+        assert!(decode(DictionaryKind::Hashmap, &bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_truncated_bytes() {
+        let bytes = encode(DictionaryKind::Hashmap, b"payload");
+
+        assert!(decode(DictionaryKind::Hashmap, &bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(DictionaryKind::Hashmap, &bytes[..4]).is_err());
+    }
+
+    #[test]
+    fn test_values() {
+        let value = vec![Some("foo".to_string()), None];
+        let mut bytes = vec![];
+        value.write_bytes(&mut bytes);
+
+        let result = Vec::<Option<String>>::read_bytes(&bytes).unwrap();
+
+        assert_eq!(result, value);
+        assert!(u32::read_bytes(&bytes[..3]).is_err());
+        assert!(bool::read_bytes(&[2]).is_err());
+    }
+}
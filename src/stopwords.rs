@@ -0,0 +1,256 @@
+use std::collections::HashSet;
+use std::collections::hash_set;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use crate::{Match, Normalization, UltraNLPResult};
+
+const CHINESE_STOPWORDS: [&str; 120] = [
+    "的", "地", "得", "了", "着", "过", "是", "在", "和", "与",
+    "及", "或", "而", "并", "且", "也", "都", "就", "才", "又",
+    "还", "再", "很", "更", "最", "太", "不", "没", "没有", "把",
+    "被", "让", "给", "对", "向", "从", "自", "于", "以", "为",
+    "因", "因为", "所以", "但", "但是", "然而", "而且", "并且", "如果", "虽然",
+    "即使", "只要", "只有", "除了", "关于", "对于", "由于", "根据", "通过", "按照",
+    "这", "那", "这个", "那个", "这些", "那些", "这里", "那里", "这样", "那样",
+    "我", "你", "您", "他", "她", "它", "我们", "你们", "他们", "她们",
+    "它们", "自己", "什么", "怎么", "怎样", "为什么", "哪", "哪里", "谁", "其",
+    "其中", "之", "之一", "所", "等", "等等", "吗", "呢", "吧", "啊",
+    "呀", "哦", "嗯", "么", "嘛", "啦", "一个", "一些", "一样", "可以",
+    "已经", "正在", "将", "会", "能", "要", "应该", "可能", "每", "各",
+];
+
+const ENGLISH_STOPWORDS: [&str; 127] = [
+    "i", "me", "my", "myself", "we", "our", "ours", "ourselves", "you", "your",
+    "yours", "yourself", "yourselves", "he", "him", "his", "himself", "she", "her", "hers",
+    "herself", "it", "its", "itself", "they", "them", "their", "theirs", "themselves", "what",
+    "which", "who", "whom", "this", "that", "these", "those", "am", "is", "are",
+    "was", "were", "be", "been", "being", "have", "has", "had", "having", "do",
+    "does", "did", "doing", "a", "an", "the", "and", "but", "if", "or",
+    "because", "as", "until", "while", "of", "at", "by", "for", "with", "about",
+    "against", "between", "into", "through", "during", "before", "after", "above", "below", "to",
+    "from", "up", "down", "in", "out", "on", "off", "over", "under", "again",
+    "further", "then", "once", "here", "there", "when", "where", "why", "how", "all",
+    "any", "both", "each", "few", "more", "most", "other", "some", "such", "no",
+    "nor", "not", "only", "own", "same", "so", "than", "too", "very", "s",
+    "t", "can", "will", "just", "don", "should", "now",
+];
+
+/// A set of stopwords, which are normalized before they are inserted or looked up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StopwordSet {
+    words: HashSet<String>,
+    normalization: Normalization,
+}
+
+impl StopwordSet {
+    pub fn new<T: AsRef<str>, I: IntoIterator<Item = T>>(words: I) -> Self {
+        Self::new_with_normalization(words, Normalization::default())
+    }
+
+    pub fn new_with_normalization<T: AsRef<str>, I: IntoIterator<Item = T>>(
+        words: I,
+        normalization: Normalization,
+    ) -> Self {
+        let mut stopwords = Self {
+            words: HashSet::new(),
+            normalization,
+        };
+        stopwords.extend(words);
+
+        stopwords
+    }
+
+    /// The built-in Chinese stopwords, mostly function words and pronouns.
+    pub fn chinese() -> Self {
+        Self::new(CHINESE_STOPWORDS)
+    }
+
+    /// The built-in English stopwords, same as the list of NLTK.
+    pub fn english() -> Self {
+        Self::new(ENGLISH_STOPWORDS)
+    }
+
+    /// Loads stopwords from a file with one word per line.
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let file = File::open(path)?;
+
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Reads stopwords with one word per line,
+    /// the surrounding whitespaces are trimmed and empty lines are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> UltraNLPResult<Self> {
+        let words = reader
+            .lines()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(
+            words
+                .iter()
+                .map(|word| word.trim())
+                .filter(|word| !word.is_empty())
+        ))
+    }
+
+    pub fn insert<T: AsRef<str>>(&mut self, word: T) -> bool {
+        self.words.insert(self.normalization.normalize(word.as_ref()))
+    }
+
+    pub fn remove<T: AsRef<str>>(&mut self, word: T) -> bool {
+        self.words.remove(&self.normalization.normalize(word.as_ref()))
+    }
+
+    pub fn extend<T: AsRef<str>, I: IntoIterator<Item = T>>(&mut self, words: I) {
+        words
+            .into_iter()
+            .for_each(|word| {
+                self.insert(word);
+            });
+    }
+
+    pub fn contains<T: AsRef<str>>(&self, word: T) -> bool {
+        self.words.contains(&self.normalization.normalize(word.as_ref()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    /// Returns the normalized stopwords in arbitrary order.
+    pub fn iter(&self) -> hash_set::Iter<'_, String> {
+        self.words.iter()
+    }
+
+    /// Drops the matches whose words are stopwords,
+    /// the ranges of the matches must refer to `text`.
+    pub fn filter_matches<'a, I>(
+        &'a self,
+        text: &'a str,
+        matches: I,
+    ) -> impl Iterator<Item = Match> + 'a
+    where
+        I: IntoIterator<Item = Match>,
+        I::IntoIter: 'a,
+    {
+        matches
+            .into_iter()
+            .filter(move |mat| {
+                mat
+                    .range()
+                    .extract(text)
+                    .is_none_or(|word| !self.contains(word))
+            })
+    }
+
+    /// Drops the words that are stopwords, e.g. from `ngrams`.
+    pub fn filter_words<'a, T, I>(&'a self, words: I) -> impl Iterator<Item = T> + 'a
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+    {
+        words
+            .into_iter()
+            .filter(move |word| !self.contains(word))
+    }
+}
+
+impl<'a> IntoIterator for &'a StopwordSet {
+    type Item = &'a String;
+    type IntoIter = hash_set::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, IdfTable, StopwordSet, TfIdfExtractor, UltraNLPError, ngrams};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+
+    #[test]
+    fn test_built_in_stopwords() {
+        let chinese = StopwordSet::chinese();
+        let english = StopwordSet::english();
+
+        assert!(chinese.contains("的"));
+        assert!(!chinese.contains("服务"));
+        assert!(english.contains("The"));
+        assert!(!english.contains("rust"));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let text = "的\n\n  了 \nThe\n";
+
+        let result = StopwordSet::from_reader(text.as_bytes()).unwrap();
+
+        assert_eq!(result.len(), 3);
+        assert!(result.contains("了"));
+        assert!(result.contains("the"));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        assert!(matches!(
+            StopwordSet::load("missing_stopwords.txt"),
+            Err(UltraNLPError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let text = "商品和服务的价格";
+        let dict = ForwardDictionary::new(vec!["商品", "服务", "价格"]).unwrap();
+        let stopwords = StopwordSet::chinese();
+
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsChars);
+        let result = stopwords.filter_matches(text, matches);
+
+        assert_eq!(
+            result
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["商品", "服务", "价格"]
+        );
+    }
+
+    #[test]
+    fn test_filter_words() {
+        let stopwords = StopwordSet::new(vec!["的"]);
+
+        let result = stopwords.filter_words(ngrams("我的书", 1));
+
+        assert_eq!(result.collect::<Vec<_>>(), vec!["我", "书"]);
+    }
+
+    #[test]
+    fn test_as_stopwords_of_extractors() {
+        let text = "the service and the price";
+        let dict = ForwardDictionary::new(vec!["the", "service", "and", "price"]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let table = IdfTable::new(vec![("service", 1.0), ("price", 1.0)]);
+
+        let result = TfIdfExtractor::new(&table)
+            .stopwords(&StopwordSet::english())
+            .extract(text, &matches, 10);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["service", "price"]
+        );
+    }
+}
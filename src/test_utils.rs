@@ -0,0 +1,14 @@
+use crate::Dictionary;
+
+/// Creates the dictionary with frequencies shared by the tests of the probabilistic segmentations.
+pub(crate) fn create_dict<D: Dictionary<Value = ()>>() -> D {
+    D::new_with_frequencies(
+        vec![
+            ("商品", 100),
+            ("和", 100),
+            ("和服", 10),
+            ("服务", 100),
+            ("你好世界", 10),
+        ]
+    ).unwrap()
+}
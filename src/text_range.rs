@@ -24,6 +24,10 @@ impl TextRange {
         self.end_index - self.start_index
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.start_index == self.end_index
+    }
+
     pub fn extract<'a>(&self, text: &'a str) -> Option<&'a str> {
         text.get(self.start_index..self.end_index)
     }
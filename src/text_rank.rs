@@ -0,0 +1,532 @@
+use std::collections::{HashMap, HashSet};
+use crate::{
+    Keyword,
+    Match,
+    Normalization,
+    TaggedMatch,
+    TextRange,
+    keyword::{is_candidate_word, normalized_words, select_top_k, DEFAULT_MIN_WORD_CHARS},
+};
+
+// PageRank在两次迭代的分数之差小于此值时停止.
+const CONVERGENCE_THRESHOLD: f64 = 1e-6;
+
+const SENTENCE_TERMINATORS: [char; 10] = ['。', '！', '？', '；', '…', '!', '?', ';', '.', '\n'];
+
+/// A sentence ranked by TextRank.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedSentence {
+    range: TextRange,
+    score: f64,
+}
+
+impl RankedSentence {
+    pub fn new(range: TextRange, score: f64) -> Self {
+        Self { range, score }
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.range
+    }
+
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+/// Extracts keywords and key sentences from the matches of any segmenter by TextRank.
+///
+/// The keywords are ranked by PageRank over a graph whose edges connect
+/// the candidate words co-occurring within a sliding window.
+#[derive(Debug, Clone)]
+pub struct TextRankExtractor {
+    stopwords: HashSet<String>,
+    allowed_tags: Option<HashSet<String>>,
+    normalization: Normalization,
+    min_word_chars: usize,
+    window_size: usize,
+    damping_factor: f64,
+    max_iterations: usize,
+}
+
+impl Default for TextRankExtractor {
+    fn default() -> Self {
+        Self {
+            stopwords: HashSet::new(),
+            allowed_tags: None,
+            normalization: Normalization::default(),
+            min_word_chars: DEFAULT_MIN_WORD_CHARS,
+            window_size: 5,
+            damping_factor: 0.85,
+            max_iterations: 100,
+        }
+    }
+}
+
+impl TextRankExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stopwords<T: AsRef<str>, I: IntoIterator<Item = T>>(mut self, stopwords: I) -> Self {
+        self.stopwords = stopwords
+            .into_iter()
+            .map(|stopword| stopword.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// Only the words with these tags are candidates in `extract_tagged`,
+    /// all tags are allowed by default.
+    pub fn allowed_tags<T: AsRef<str>, I: IntoIterator<Item = T>>(mut self, tags: I) -> Self {
+        self.allowed_tags = Some(
+            tags
+                .into_iter()
+                .map(|tag| tag.as_ref().to_string())
+                .collect()
+        );
+        self
+    }
+
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Words with fewer chars are not keywords, defaults to 2.
+    pub fn min_word_chars(mut self, min_word_chars: usize) -> Self {
+        self.min_word_chars = min_word_chars;
+        self
+    }
+
+    /// Words co-occur if their distance is less than the window size, defaults to 5.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Defaults to 0.85.
+    pub fn damping_factor(mut self, damping_factor: f64) -> Self {
+        self.damping_factor = damping_factor;
+        self
+    }
+
+    /// Defaults to 100, PageRank stops early when the scores converge.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Returns the `top_k` keywords with the highest scores, the highest score is 1,
+    /// the ranges of the matches must refer to `text`.
+    pub fn extract(&self, text: &str, matches: &[Match], top_k: usize) -> Vec<Keyword> {
+        let words_with_candidacy = normalized_words(text, matches, self.normalization)
+            .into_iter()
+            .map(|(word, range)| {
+                let is_candidate = self.is_candidate_word(&word);
+
+                (word, range, is_candidate)
+            })
+            .collect::<Vec<_>>();
+
+        self.rank_words(words_with_candidacy, top_k)
+    }
+
+    /// Same as `extract`, but only the words with the allowed tags are candidates.
+    pub fn extract_tagged(
+        &self,
+        text: &str,
+        tagged_matches: &[TaggedMatch],
+        top_k: usize,
+    ) -> Vec<Keyword> {
+        // 每个词与它自己的词性一起处理, 无法提取的词被跳过.
+        let words_with_candidacy = tagged_matches
+            .iter()
+            .filter_map(|tagged_match| {
+                let range = tagged_match.mat().range();
+                let word = self.normalization.normalize(range.extract(text)?);
+                let is_allowed_tag = self.allowed_tags
+                    .as_ref()
+                    .is_none_or(|tags| tags.contains(tagged_match.tag()));
+                let is_candidate = is_allowed_tag && self.is_candidate_word(&word);
+
+                Some((word, range, is_candidate))
+            })
+            .collect::<Vec<_>>();
+
+        self.rank_words(words_with_candidacy, top_k)
+    }
+
+    /// Splits the text into sentences and returns the `top_k` sentences with the highest scores,
+    /// the similarity of two sentences is based on their common candidate words.
+    pub fn extract_sentences(
+        &self,
+        text: &str,
+        matches: &[Match],
+        top_k: usize,
+    ) -> Vec<RankedSentence> {
+        let sentence_ranges = split_sentences(text);
+        let mut words = normalized_words(text, matches, self.normalization)
+            .into_iter()
+            .filter(|(word, _)| self.is_candidate_word(word))
+            .collect::<Vec<_>>();
+        words.sort_by_key(|(_, range)| range.start_index());
+
+        // 每个句子中的候选词, 跨越句子边界的词属于它开始的句子.
+        // 词和句子都按位置排序, 同时遍历两者.
+        let mut words_of_sentences: Vec<HashSet<&str>> = vec![HashSet::new(); sentence_ranges.len()];
+        let mut index_of_sentence = 0;
+        words
+            .iter()
+            .for_each(|(word, range)| {
+                while sentence_ranges
+                    .get(index_of_sentence)
+                    .is_some_and(|sentence_range| sentence_range.end_index() <= range.start_index()) {
+                    index_of_sentence += 1;
+                }
+
+                if let Some(sentence_range) = sentence_ranges.get(index_of_sentence)
+                && sentence_range.start_index() <= range.start_index() {
+                    words_of_sentences[index_of_sentence].insert(word.as_str());
+                }
+            });
+
+        let mut graph: Vec<HashMap<usize, f64>> = vec![HashMap::new(); sentence_ranges.len()];
+        (0..words_of_sentences.len()).for_each(|i| {
+            ((i + 1)..words_of_sentences.len()).for_each(|j| {
+                let similarity = sentence_similarity(&words_of_sentences[i], &words_of_sentences[j]);
+                if similarity > 0.0 {
+                    graph[i].insert(j, similarity);
+                    graph[j].insert(i, similarity);
+                }
+            });
+        });
+
+        let scores = self.page_rank(&graph);
+        let mut sentences = sentence_ranges
+            .into_iter()
+            .zip(scores)
+            .map(|(range, score)| RankedSentence::new(range, score))
+            .collect::<Vec<_>>();
+        // 分数相同时保持句子的原始顺序.
+        sentences.sort_by(|a, b| b.score.total_cmp(&a.score));
+        sentences.truncate(top_k);
+
+        sentences
+    }
+
+    fn is_candidate_word(&self, word: &str) -> bool {
+        is_candidate_word(word, &self.stopwords, self.min_word_chars)
+    }
+
+    fn rank_words(
+        &self,
+        words_with_candidacy: Vec<(String, TextRange, bool)>,
+        top_k: usize,
+    ) -> Vec<Keyword> {
+        let mut indexes_of_nodes: HashMap<&str, usize> = HashMap::new();
+        let mut nodes: Vec<(&str, Vec<TextRange>)> = vec![];
+        // 每个词对应的节点, 非候选词没有节点.
+        let nodes_of_words = words_with_candidacy
+            .iter()
+            .map(|(word, range, is_candidate)| {
+                if !is_candidate {
+                    return None;
+                }
+
+                let index = *indexes_of_nodes
+                    .entry(word.as_str())
+                    .or_insert_with(|| {
+                        nodes.push((word.as_str(), vec![]));
+
+                        nodes.len() - 1
+                    });
+                nodes[index].1.push(*range);
+
+                Some(index)
+            })
+            .collect::<Vec<_>>();
+
+        let mut graph: Vec<HashMap<usize, f64>> = vec![HashMap::new(); nodes.len()];
+        nodes_of_words
+            .iter()
+            .enumerate()
+            .for_each(|(i, node)| {
+                if let Some(node) = node {
+                    nodes_of_words
+                        .iter()
+                        .skip(i + 1)
+                        .take(self.window_size.saturating_sub(1))
+                        .flatten()
+                        .filter(|other_node| *other_node != node)
+                        .for_each(|other_node| {
+                            *graph[*node].entry(*other_node).or_insert(0.0) += 1.0;
+                            *graph[*other_node].entry(*node).or_insert(0.0) += 1.0;
+                        });
+                }
+            });
+
+        let scores = self.page_rank(&graph);
+        let max_score = scores
+            .iter()
+            .copied()
+            .fold(0.0, f64::max);
+        let keywords = nodes
+            .into_iter()
+            .zip(scores)
+            .map(|((word, ranges), score)| Keyword::new(word, score / max_score, ranges))
+            .collect();
+
+        select_top_k(keywords, top_k)
+    }
+
+    /// Runs the weighted PageRank over an undirected graph given by adjacency maps.
+    fn page_rank(&self, graph: &[HashMap<usize, f64>]) -> Vec<f64> {
+        let total_weights = graph
+            .iter()
+            .map(|edges| edges.values().sum::<f64>())
+            .collect::<Vec<_>>();
+        let mut scores = vec![1.0; graph.len()];
+
+        for _ in 0..self.max_iterations {
+            let next_scores = graph
+                .iter()
+                .map(|edges| {
+                    let rank = edges
+                        .iter()
+                        .map(|(other_node, weight)| {
+                            weight / total_weights[*other_node] * scores[*other_node]
+                        })
+                        .sum::<f64>();
+
+                    (1.0 - self.damping_factor) + self.damping_factor * rank
+                })
+                .collect::<Vec<_>>();
+            let is_converged = scores
+                .iter()
+                .zip(&next_scores)
+                .all(|(score, next_score)| (score - next_score).abs() < CONVERGENCE_THRESHOLD);
+
+            scores = next_scores;
+            if is_converged {
+                break;
+            }
+        }
+
+        scores
+    }
+}
+
+// 句子之间的相似度, 见TextRank论文.
+fn sentence_similarity(a: &HashSet<&str>, b: &HashSet<&str>) -> f64 {
+    let common_words = a.intersection(b).count();
+    if common_words == 0 {
+        return 0.0;
+    }
+
+    let denominator = (a.len() as f64).ln() + (b.len() as f64).ln();
+    if denominator > 0.0 {
+        common_words as f64 / denominator
+    } else {
+        // 两个句子都只有同一个词.
+        common_words as f64
+    }
+}
+
+/// Splits the text into sentences by terminal punctuations and newlines,
+/// the surrounding whitespaces of the sentences are trimmed.
+///
+/// A '.' terminates a sentence only if it is followed by a whitespace or the end of the text,
+/// so decimals such as "3.5" are kept.
+pub(crate) fn split_sentences(text: &str) -> Vec<TextRange> {
+    let mut results: Vec<TextRange> = vec![];
+
+    let mut start_index = 0;
+    let mut chars = text
+        .char_indices()
+        .peekable();
+    while let Some((index, char)) = chars.next() {
+        let next_char = chars.peek().map(|(_, char)| *char);
+        let is_terminator = if char == '.' {
+            next_char.is_none_or(char::is_whitespace)
+        } else {
+            SENTENCE_TERMINATORS.contains(&char)
+        };
+
+        if is_terminator {
+            let end_index = index + char.len_utf8();
+            if let Some(range) = trim_range(text, start_index, end_index) {
+                results.push(range);
+            }
+
+            start_index = end_index;
+        }
+    }
+    if let Some(range) = trim_range(text, start_index, text.len()) {
+        results.push(range);
+    }
+
+    results
+}
+
+fn trim_range(text: &str, start_index: usize, end_index: usize) -> Option<TextRange> {
+    let sentence = &text[start_index..end_index];
+    let trimmed_sentence = sentence.trim();
+    // 只有标点的句子没有意义.
+    if !trimmed_sentence.chars().any(char::is_alphanumeric) {
+        return None;
+    }
+
+    let start_index = start_index + (sentence.len() - sentence.trim_start().len());
+
+    Some(TextRange::new(start_index, start_index + trimmed_sentence.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, Match, TaggedMatch, TextRange, TextRankExtractor};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+    use crate::text_rank::split_sentences;
+
+    #[test]
+    fn test_extract() {
+        let text = "程序员使用编程语言编写程序, 程序员调试程序, 程序员喜欢编程语言";
+        let dict = ForwardDictionary::new(
+            vec!["程序员", "使用", "编程语言", "编写", "程序", "调试", "喜欢"]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+
+        let result = TextRankExtractor::new().extract(text, &matches, 3);
+
+        assert_eq!(result[0].word(), "程序员");
+        assert_eq!(result[0].score(), 1.0);
+        assert_eq!(result[0].ranges().len(), 3);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn test_extract_with_stopwords() {
+        let text = "程序员使用编程语言编写程序, 程序员调试程序, 程序员喜欢编程语言";
+        let dict = ForwardDictionary::new(
+            vec!["程序员", "使用", "编程语言", "编写", "程序", "调试", "喜欢"]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+
+        let result = TextRankExtractor::new()
+            .stopwords(vec!["程序员"])
+            .extract(text, &matches, 10);
+
+        assert!(result.iter().all(|x| x.word() != "程序员"));
+        assert_eq!(result.len(), 6);
+    }
+
+    #[test]
+    fn test_extract_tagged() {
+        let text = "程序员使用编程语言";
+        let dict = ForwardDictionary::new(vec!["程序员", "使用", "编程语言"]).unwrap();
+        let tagged_matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords)
+            .into_iter()
+            .zip(["n", "v", "n"])
+            .map(|(mat, tag)| TaggedMatch::new(mat, tag))
+            .collect::<Vec<_>>();
+
+        let result = TextRankExtractor::new()
+            .allowed_tags(vec!["n"])
+            .extract_tagged(text, &tagged_matches, 10);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["程序员", "编程语言"]
+        );
+    }
+
+    #[test]
+    fn test_extract_tagged_with_invalid_range() {
+        let text = "程序员使用编程语言";
+        let dict = ForwardDictionary::new(vec!["程序员", "使用", "编程语言"]).unwrap();
+        let tagged_matches = std::iter::once(Match::new(TextRange::new(100, 200), None))
+            .chain(segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords))
+            .zip(["v", "n", "v", "n"])
+            .map(|(mat, tag)| TaggedMatch::new(mat, tag))
+            .collect::<Vec<_>>();
+
+        let result = TextRankExtractor::new()
+            .allowed_tags(vec!["n"])
+            .extract_tagged(text, &tagged_matches, 10);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["程序员", "编程语言"]
+        );
+    }
+
+    #[test]
+    fn test_extract_sentences() {
+        let text = "程序员编写程序。今天天气很好。程序员调试程序, 程序员测试程序。";
+        let dict = ForwardDictionary::new(
+            vec!["程序员", "编写", "程序", "今天", "天气", "调试", "测试"]
+        ).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+
+        let result = TextRankExtractor::new().extract_sentences(text, &matches, 2);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.range().extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["程序员编写程序。", "程序员调试程序, 程序员测试程序。"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        let text = " 你好! 世界。。\nhello world ";
+
+        let result = split_sentences(text);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["你好!", "世界。", "hello world"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_at_periods() {
+        let text = "价格是3.5元. 见ultranlp.rs.第1. 2步.";
+
+        let result = split_sentences(text);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["价格是3.5元.", "见ultranlp.rs.第1.", "2步."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_after_numbers() {
+        let text = "The price is 25. The tax is 3. Nothing else.";
+
+        let result = split_sentences(text);
+
+        assert_eq!(
+            result
+                .into_iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["The price is 25.", "The tax is 3.", "Nothing else."]
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use crate::{
+    Keyword,
+    Match,
+    Normalization,
+    TextRange,
+    UltraNLPError,
+    UltraNLPResult,
+    keyword::{is_candidate_word, normalized_words, select_top_k, DEFAULT_MIN_WORD_CHARS},
+    utils::sorted_entries,
+};
+
+/// The inverse document frequencies of words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdfTable {
+    idfs: HashMap<String, f64>,
+    // 未出现在表中的词的IDF.
+    default_idf: f64,
+}
+
+impl IdfTable {
+    /// Creates a table from words with their IDFs,
+    /// the IDF of unknown words is the median of the IDFs.
+    pub fn new<T: AsRef<str>, I: IntoIterator<Item = (T, f64)>>(
+        words_with_idfs: I
+    ) -> Self {
+        let idfs = words_with_idfs
+            .into_iter()
+            .map(|(word, idf)| (word.as_ref().to_string(), idf))
+            .collect::<HashMap<_, _>>();
+        let default_idf = median(idfs.values().copied().collect());
+
+        Self { idfs, default_idf }
+    }
+
+    /// Computes the IDFs from a corpus, each document is a sequence of words.
+    ///
+    /// The IDF of a word is `ln((1 + documents) / (1 + documents containing the word)) + 1`.
+    pub fn from_documents<
+        T: AsRef<str>,
+        D: IntoIterator<Item = T>,
+        I: IntoIterator<Item = D>
+    >(
+        documents: I
+    ) -> UltraNLPResult<Self> {
+        let mut document_frequencies: HashMap<String, usize> = HashMap::new();
+        let mut number_of_documents = 0;
+
+        documents
+            .into_iter()
+            .for_each(|words| {
+                number_of_documents += 1;
+
+                words
+                    .into_iter()
+                    .map(|word| word.as_ref().to_string())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .for_each(|word| *document_frequencies.entry(word).or_insert(0) += 1);
+            });
+
+        if number_of_documents == 0 {
+            return Err(UltraNLPError::EmptyCorpus);
+        }
+
+        let idf_of = |document_frequency: usize| {
+            ((1 + number_of_documents) as f64 / (1 + document_frequency) as f64).ln() + 1.0
+        };
+        let idfs = document_frequencies
+            .into_iter()
+            .map(|(word, document_frequency)| (word, idf_of(document_frequency)))
+            .collect();
+
+        Ok(Self { idfs, default_idf: idf_of(0) })
+    }
+
+    /// Loads a table from a file written by `IdfTable::save` or a jieba style IDF file.
+    pub fn load<P: AsRef<Path>>(path: P) -> UltraNLPResult<Self> {
+        let file = File::open(path)?;
+
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Reads a table from lines of `word idf` separated by whitespaces,
+    /// empty lines are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> UltraNLPResult<Self> {
+        let words_with_idfs = reader
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) => return Some(Err(UltraNLPError::from(err))),
+                };
+                let invalid_line = || {
+                    UltraNLPError::InvalidLine { format: "IDF table", line: index + 1 }
+                };
+
+                let fields = line.split_whitespace().collect::<Vec<_>>();
+                match fields.as_slice() {
+                    [] => None,
+                    [word, idf] => Some(
+                        idf
+                            .parse::<f64>()
+                            .map(|idf| (word.to_string(), idf))
+                            .map_err(|_| invalid_line())
+                    ),
+                    _ => Some(Err(invalid_line())),
+                }
+            })
+            .collect::<UltraNLPResult<Vec<_>>>()?;
+
+        Ok(Self::new(words_with_idfs))
+    }
+
+    /// Saves the table to a file that can be loaded by `IdfTable::load`.
+    ///
+    /// The IDF of unknown words is not saved, it is the median of the IDFs after loading.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> UltraNLPResult<()> {
+        let file = File::create(path)?;
+
+        self.to_writer(file)
+    }
+
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> UltraNLPResult<()> {
+        sorted_entries(&self.idfs)
+            .into_iter()
+            .try_for_each(|(word, idf)| writeln!(writer, "{} {}", word, idf))
+            .map_err(UltraNLPError::from)
+    }
+
+    pub fn with_default_idf(mut self, default_idf: f64) -> Self {
+        self.default_idf = default_idf;
+        self
+    }
+
+    pub fn default_idf(&self) -> f64 {
+        self.default_idf
+    }
+
+    pub fn idf(&self, word: &str) -> f64 {
+        self.idfs
+            .get(word)
+            .copied()
+            .unwrap_or(self.default_idf)
+    }
+}
+
+/// Extracts keywords from the matches of any segmenter by TF-IDF.
+///
+/// Words are normalized before they are counted and looked up in the IDF table and the stopwords,
+/// so the table and the stopwords should contain normalized words.
+#[derive(Debug, Clone)]
+pub struct TfIdfExtractor<'a> {
+    idf_table: &'a IdfTable,
+    stopwords: HashSet<String>,
+    normalization: Normalization,
+    min_word_chars: usize,
+}
+
+impl<'a> TfIdfExtractor<'a> {
+    pub fn new(idf_table: &'a IdfTable) -> Self {
+        Self {
+            idf_table,
+            stopwords: HashSet::new(),
+            normalization: Normalization::default(),
+            min_word_chars: DEFAULT_MIN_WORD_CHARS,
+        }
+    }
+
+    pub fn stopwords<T: AsRef<str>, I: IntoIterator<Item = T>>(mut self, stopwords: I) -> Self {
+        self.stopwords = stopwords
+            .into_iter()
+            .map(|stopword| stopword.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Words with fewer chars are not keywords, defaults to 2.
+    pub fn min_word_chars(mut self, min_word_chars: usize) -> Self {
+        self.min_word_chars = min_word_chars;
+        self
+    }
+
+    /// Returns the `top_k` keywords with the highest TF-IDF,
+    /// the ranges of the matches must refer to `text`.
+    pub fn extract(&self, text: &str, matches: &[Match], top_k: usize) -> Vec<Keyword> {
+        let words = normalized_words(text, matches, self.normalization)
+            .into_iter()
+            .filter(|(word, _)| is_candidate_word(word, &self.stopwords, self.min_word_chars))
+            .collect::<Vec<_>>();
+        let total = words.len() as f64;
+
+        let mut ranges_of_words: HashMap<String, Vec<TextRange>> = HashMap::new();
+        words
+            .into_iter()
+            .for_each(|(word, range)| {
+                ranges_of_words
+                    .entry(word)
+                    .or_default()
+                    .push(range)
+            });
+
+        let keywords = ranges_of_words
+            .into_iter()
+            .map(|(word, ranges)| {
+                let tf = ranges.len() as f64 / total;
+                let score = tf * self.idf_table.idf(&word);
+
+                Keyword::new(word, score, ranges)
+            })
+            .collect();
+
+        select_top_k(keywords, top_k)
+    }
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.sort_by(|a, b| a.total_cmp(b));
+    values[values.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, IdfTable, TfIdfExtractor, UltraNLPError};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+
+    #[test]
+    fn test_from_documents() {
+        let table = IdfTable::from_documents(vec![
+            vec!["商品", "服务"],
+            vec!["商品", "价格"],
+            vec!["服务", "服务"],
+        ]).unwrap();
+
+        assert!(table.idf("价格") > table.idf("商品"));
+        assert_eq!(table.idf("商品"), table.idf("服务"));
+        assert!(table.idf("未知") > table.idf("价格"));
+    }
+
+    #[test]
+    fn test_from_empty_documents() {
+        let documents: Vec<Vec<&str>> = vec![];
+
+        assert!(matches!(IdfTable::from_documents(documents), Err(UltraNLPError::EmptyCorpus)));
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let text = "商品 5.0\n\n服务 3.0\n价格 4.0\n";
+
+        let result = IdfTable::from_reader(text.as_bytes()).unwrap();
+
+        assert_eq!(result.idf("商品"), 5.0);
+        assert_eq!(result.default_idf(), 4.0);
+    }
+
+    #[test]
+    fn test_from_invalid_reader() {
+        let text = "商品 5.0\n服务\n";
+
+        assert!(matches!(
+            IdfTable::from_reader(text.as_bytes()),
+            Err(UltraNLPError::InvalidLine { format: "IDF table", line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_to_writer_and_from_reader() {
+        let table = IdfTable::new(vec![("商品", 5.0), ("服务", 3.0)]);
+        let mut buffer: Vec<u8> = vec![];
+
+        table.to_writer(&mut buffer).unwrap();
+        let result = IdfTable::from_reader(buffer.as_slice()).unwrap();
+
+        assert_eq!(result, table);
+    }
+
+    #[test]
+    fn test_extract() {
+        let text = "商品和服务, 服务的价格, Service";
+        let dict = ForwardDictionary::new(vec!["商品", "服务", "价格", "service"]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsChars);
+        let table = IdfTable::new(vec![("商品", 1.0), ("服务", 1.0), ("价格", 3.0)]);
+
+        let result = TfIdfExtractor::new(&table)
+            .stopwords(vec!["service"])
+            .extract(text, &matches, 2);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["价格", "服务"]
+        );
+        assert_eq!(
+            result[1]
+                .ranges()
+                .iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["服务", "服务"]
+        );
+    }
+
+    #[test]
+    fn test_extract_with_normalization() {
+        let text = "Rust rust RUST";
+        let dict = ForwardDictionary::new(vec!["rust"]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+        let table = IdfTable::new(vec![("rust", 1.0)]);
+
+        let result = TfIdfExtractor::new(&table).extract(text, &matches, 10);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].word(), "rust");
+        assert_eq!(result[0].ranges().len(), 3);
+    }
+}
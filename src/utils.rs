@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use crate::TextRange;
 
 pub fn split_as_char_ranges<'a>(
@@ -12,6 +13,17 @@ pub fn split_as_char_ranges<'a>(
         })
 }
 
+/// Collects the entries of the map in the order of their keys,
+/// so that the files written from the map are stable.
+pub fn sorted_entries<K: Ord, V>(map: &HashMap<K, V>) -> Vec<(&K, &V)> {
+    let mut entries = map
+        .iter()
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    entries
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::split_as_char_ranges;
@@ -20,7 +32,7 @@ mod tests {
     fn test_split_as_char_ranges() {
         let text = " 你好世界, hello world ";
 
-        let result = split_as_char_ranges(&text).collect::<Vec<_>>();
+        let result = split_as_char_ranges(text).collect::<Vec<_>>();
 
         assert_eq!(
             result
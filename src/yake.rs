@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+use crate::{
+    Keyword,
+    Match,
+    Normalization,
+    TextRange,
+    keyword::{normalized_words, phrase_text, select_top_k, split_phrases},
+    text_rank::split_sentences,
+};
+
+/// Extracts key phrases from the matches of any segmenter by YAKE (Yet Another Keyword Extractor).
+///
+/// The words are scored by statistical features: casing, position, frequency,
+/// relatedness to context and the spread over sentences.
+/// The phrase candidates are the n-grams of the phrases split at stopwords and punctuations.
+///
+/// A lower YAKE score means a more important phrase,
+/// so the score of the returned keywords is its reciprocal.
+#[derive(Debug, Clone)]
+pub struct YakeExtractor {
+    stopwords: HashSet<String>,
+    normalization: Normalization,
+    max_phrase_words: usize,
+    window_size: usize,
+}
+
+impl Default for YakeExtractor {
+    fn default() -> Self {
+        Self {
+            stopwords: HashSet::new(),
+            normalization: Normalization::default(),
+            max_phrase_words: 3,
+            window_size: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WordStatistics {
+    frequency: usize,
+    capitalized_frequency: usize,
+    acronym_frequency: usize,
+    // 每次出现所在的句子.
+    indexes_of_sentences: Vec<usize>,
+    left_words: Vec<usize>,
+    right_words: Vec<usize>,
+}
+
+impl YakeExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stopwords<T: AsRef<str>, I: IntoIterator<Item = T>>(mut self, stopwords: I) -> Self {
+        self.stopwords = stopwords
+            .into_iter()
+            .map(|stopword| stopword.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Phrase candidates have at most this number of words, defaults to 3.
+    pub fn max_phrase_words(mut self, max_phrase_words: usize) -> Self {
+        self.max_phrase_words = max_phrase_words;
+        self
+    }
+
+    /// The window of the words counted as the context of a word, defaults to 1.
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size;
+        self
+    }
+
+    /// Returns the `top_k` phrases with the highest scores,
+    /// the ranges of the matches must refer to `text`.
+    pub fn extract(&self, text: &str, matches: &[Match], top_k: usize) -> Vec<Keyword> {
+        let word_scores = self.score_words(text, matches);
+
+        let phrases = split_phrases(text, matches, self.normalization, &self.stopwords);
+
+        let mut ranges_of_phrases: HashMap<String, (Vec<&str>, Vec<TextRange>)> = HashMap::new();
+        phrases
+            .iter()
+            .for_each(|phrase| {
+                (1..=self.max_phrase_words).for_each(|n| {
+                    phrase
+                        .windows(n)
+                        .for_each(|ngram| {
+                            if let Some((text, range)) = phrase_text(text, ngram, self.normalization) {
+                                ranges_of_phrases
+                                    .entry(text)
+                                    .or_insert_with(|| {
+                                        let words = ngram
+                                            .iter()
+                                            .map(|(word, _)| word.as_str())
+                                            .collect();
+
+                                        (words, vec![])
+                                    })
+                                    .1
+                                    .push(range);
+                            }
+                        });
+                });
+            });
+
+        let keywords = ranges_of_phrases
+            .into_iter()
+            .map(|(phrase, (words, mut ranges))| {
+                let scores = words
+                    .iter()
+                    .map(|word| word_scores[*word])
+                    .collect::<Vec<_>>();
+                let product = scores.iter().product::<f64>();
+                let sum = scores.iter().sum::<f64>();
+                let score = product / (ranges.len() as f64 * (1.0 + sum));
+
+                ranges.sort_by_key(|range| range.start_index());
+
+                Keyword::new(phrase, 1.0 / score, ranges)
+            })
+            .collect();
+
+        select_top_k(keywords, top_k)
+    }
+
+    /// Returns the YAKE scores of the words other than stopwords.
+    fn score_words(&self, text: &str, matches: &[Match]) -> HashMap<String, f64> {
+        let sentences = split_sentences(text);
+        let number_of_sentences = sentences.len().max(1) as f64;
+
+        let mut indexes_of_words: HashMap<String, usize> = HashMap::new();
+        let mut words: Vec<(String, WordStatistics)> = vec![];
+        // 每个句子中的词, 用于统计上下文.
+        let mut words_of_sentences: Vec<Vec<usize>> = vec![vec![]; sentences.len()];
+        let mut last_index_of_sentence: Option<usize> = None;
+        normalized_words(text, matches, self.normalization)
+            .into_iter()
+            .filter(|(word, _)| word.chars().any(char::is_alphanumeric))
+            .for_each(|(word, range)| {
+                let index_of_sentence = sentences
+                    .partition_point(|sentence| sentence.end_index() <= range.start_index());
+                let is_first_word_of_sentence = last_index_of_sentence != Some(index_of_sentence);
+                last_index_of_sentence = Some(index_of_sentence);
+
+                if self.stopwords.contains(&word) {
+                    return;
+                }
+
+                let original_word = range.extract(text).unwrap_or_default();
+                let index = *indexes_of_words
+                    .entry(word.clone())
+                    .or_insert_with(|| {
+                        words.push((word, WordStatistics::default()));
+
+                        words.len() - 1
+                    });
+                let statistics = &mut words[index].1;
+
+                statistics.frequency += 1;
+                if is_acronym(original_word) {
+                    statistics.acronym_frequency += 1;
+                } else if !is_first_word_of_sentence && is_capitalized(original_word) {
+                    statistics.capitalized_frequency += 1;
+                }
+                statistics.indexes_of_sentences.push(index_of_sentence);
+                if let Some(words_of_sentence) = words_of_sentences.get_mut(index_of_sentence) {
+                    words_of_sentence.push(index);
+                }
+            });
+
+        words_of_sentences
+            .iter()
+            .for_each(|words_of_sentence| {
+                words_of_sentence
+                    .iter()
+                    .enumerate()
+                    .for_each(|(i, left_word)| {
+                        words_of_sentence
+                            .iter()
+                            .skip(i + 1)
+                            .take(self.window_size)
+                            .for_each(|right_word| {
+                                words[*left_word].1.right_words.push(*right_word);
+                                words[*right_word].1.left_words.push(*left_word);
+                            });
+                    });
+            });
+
+        let frequencies = words
+            .iter()
+            .map(|(_, statistics)| statistics.frequency as f64)
+            .collect::<Vec<_>>();
+        let max_frequency = frequencies
+            .iter()
+            .copied()
+            .fold(0.0, f64::max);
+        let mean_frequency = frequencies.iter().sum::<f64>() / frequencies.len().max(1) as f64;
+        let standard_deviation_of_frequencies = (
+            frequencies
+                .iter()
+                .map(|frequency| (frequency - mean_frequency).powi(2))
+                .sum::<f64>()
+            / frequencies.len().max(1) as f64
+        ).sqrt();
+
+        words
+            .into_iter()
+            .map(|(word, statistics)| {
+                let frequency = statistics.frequency as f64;
+
+                let casing = statistics.capitalized_frequency.max(statistics.acronym_frequency) as f64
+                    / (1.0 + frequency.ln());
+                let position = (3.0 + median(&statistics.indexes_of_sentences)).ln().ln();
+                let normalized_frequency = frequency
+                    / (mean_frequency + standard_deviation_of_frequencies);
+                let relatedness = 1.0
+                    + (dispersion(&statistics.left_words) + dispersion(&statistics.right_words))
+                    * frequency / max_frequency;
+                let spread = statistics.indexes_of_sentences
+                    .iter()
+                    .collect::<HashSet<_>>()
+                    .len() as f64
+                    / number_of_sentences;
+
+                let score = relatedness * position
+                    / (casing + normalized_frequency / relatedness + spread / relatedness);
+
+                (word, score)
+            })
+            .collect()
+    }
+}
+
+// 不同的上下文词占所有上下文词的比例.
+fn dispersion(context_words: &[usize]) -> f64 {
+    if context_words.is_empty() {
+        return 0.0;
+    }
+
+    context_words
+        .iter()
+        .collect::<HashSet<_>>()
+        .len() as f64
+        / context_words.len() as f64
+}
+
+fn median(values: &[usize]) -> f64 {
+    let mut values = values.to_vec();
+    values.sort();
+
+    match values.len() {
+        0 => 0.0,
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) as f64 / 2.0,
+        len => values[len / 2] as f64,
+    }
+}
+
+fn is_acronym(word: &str) -> bool {
+    let mut letters = word
+        .chars()
+        .filter(|char| char.is_alphabetic())
+        .peekable();
+
+    letters.peek().is_some()
+    && word.chars().count() > 1
+    && letters.all(char::is_uppercase)
+}
+
+fn is_capitalized(word: &str) -> bool {
+    word
+        .chars()
+        .next()
+        .is_some_and(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BehaviorForUnmatched, YakeExtractor};
+    use crate::daachorse::{segment_forward_longest, ForwardDictionary};
+    use crate::yake::{is_acronym, median};
+
+    #[test]
+    fn test_extract() {
+        let text = "The NLP library segments text. \
+            We use the NLP library to segment text quickly. \
+            The weather is nice.";
+        let dict = ForwardDictionary::new(vec![
+            "the", "nlp", "library", "segments", "text", "we", "use", "to",
+            "segment", "quickly", "weather", "is", "nice",
+        ]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsChars);
+
+        let result = YakeExtractor::new()
+            .stopwords(vec!["the", "we", "to", "is"])
+            .extract(text, &matches, 3);
+
+        assert_eq!(
+            result
+                .iter()
+                .map(|x| x.word())
+                .collect::<Vec<_>>(),
+            vec!["nlp library segments", "nlp library", "library segments text"]
+        );
+        assert_eq!(
+            result[1]
+                .ranges()
+                .iter()
+                .map(|x| x.extract(text).unwrap())
+                .collect::<Vec<_>>(),
+            vec!["NLP library", "NLP library"]
+        );
+    }
+
+    #[test]
+    fn test_extract_with_max_phrase_words() {
+        let text = "程序员使用编程语言编写程序";
+        let dict = ForwardDictionary::new(vec!["程序员", "使用", "编程语言", "编写", "程序"]).unwrap();
+        let matches = segment_forward_longest(text, &dict, BehaviorForUnmatched::KeepAsWords);
+
+        let result = YakeExtractor::new()
+            .stopwords(vec!["使用"])
+            .max_phrase_words(2)
+            .extract(text, &matches, 10);
+
+        assert_eq!(result.len(), 6);
+        assert!(result.iter().all(|x| x.word() != "编程语言编写程序"));
+        assert!(result.iter().any(|x| x.word() == "编程语言编写"));
+    }
+
+    #[test]
+    fn test_is_acronym() {
+        assert!(is_acronym("NLP"));
+        assert!(is_acronym("GPT4"));
+        assert!(!is_acronym("Nlp"));
+        assert!(!is_acronym("A"));
+        assert!(!is_acronym("自然语言"));
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(&[3, 1, 2]), 2.0);
+        assert_eq!(median(&[4, 1, 2, 3]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+    }
+}